@@ -0,0 +1,151 @@
+// NTSC rates in CPU cycles
+const DMC_RATE_TABLE: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54,
+];
+
+#[derive(Debug, Clone)]
+pub struct Dmc {
+    pub irq_pending: bool,
+    irq_enabled: bool,
+    loop_flag: bool,
+    timer: u16,
+    timer_period: u16,
+    output_level: u8,
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+    shift_register: u8,
+    bits_remaining: u8,
+    silence: bool,
+}
+
+impl Default for Dmc {
+    fn default() -> Self {
+        Dmc::new()
+    }
+}
+
+impl Dmc {
+    pub fn new() -> Self {
+        Dmc {
+            irq_pending: false,
+            irq_enabled: false,
+            loop_flag: false,
+            timer: 0,
+            timer_period: DMC_RATE_TABLE[0],
+            output_level: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+            current_address: 0xC000,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+        }
+    }
+
+    pub fn write(&mut self, register: u16, value: u8) {
+        match register & 0x03 {
+            0 => {
+                self.irq_enabled = value & 0x80 != 0;
+                if !self.irq_enabled {
+                    self.irq_pending = false;
+                }
+                self.loop_flag = value & 0x40 != 0;
+                self.timer_period = DMC_RATE_TABLE[(value & 0x0F) as usize];
+            }
+            1 => {
+                self.output_level = value & 0x7F;
+            }
+            2 => {
+                self.sample_address = 0xC000 | ((value as u16) << 6);
+            }
+            _ => {
+                self.sample_length = ((value as u16) << 4) | 1;
+            }
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.irq_pending = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.bytes_remaining > 0
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    pub fn read_request(&self) -> Option<u16> {
+        // The memory reader refills the sample buffer as soon as it empties
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_address)
+        } else {
+            None
+        }
+    }
+
+    pub fn fill(&mut self, value: u8) {
+        self.sample_buffer = Some(value);
+        self.current_address = if self.current_address == 0xFFFF {
+            0x8000
+        } else {
+            self.current_address + 1
+        };
+        self.bytes_remaining -= 1;
+        if self.bytes_remaining == 0 {
+            if self.loop_flag {
+                self.restart();
+            } else if self.irq_enabled {
+                self.irq_pending = true;
+            }
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // Clocked every CPU cycle, the rate table is in CPU cycles
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period - 1;
+
+        if !self.silence {
+            if self.shift_register & 0x01 != 0 {
+                if self.output_level <= 125 {
+                    self.output_level += 2;
+                }
+            } else if self.output_level >= 2 {
+                self.output_level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            // Start a new output cycle
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(value) => {
+                    self.silence = false;
+                    self.shift_register = value;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        self.output_level
+    }
+}
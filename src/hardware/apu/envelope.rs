@@ -0,0 +1,81 @@
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14, 12, 16, 24, 18, 48, 20, 96, 22,
+    192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+#[derive(Default, Debug, Clone)]
+pub struct Envelope {
+    pub start: bool,
+    pub loop_flag: bool,
+    pub constant: bool,
+    pub period: u8, // Also the constant volume
+    divider: u8,
+    decay: u8,
+}
+
+impl Envelope {
+    pub fn write(&mut self, value: u8) {
+        self.loop_flag = value & 0x20 != 0;
+        self.constant = value & 0x10 != 0;
+        self.period = value & 0x0F;
+    }
+
+    pub fn clock(&mut self) {
+        // Clocked by the frame counter every quarter frame
+        if self.start {
+            self.start = false;
+            self.decay = 15;
+            self.divider = self.period;
+        } else if self.divider == 0 {
+            self.divider = self.period;
+            if self.decay > 0 {
+                self.decay -= 1;
+            } else if self.loop_flag {
+                self.decay = 15;
+            }
+        } else {
+            self.divider -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.constant {
+            self.period
+        } else {
+            self.decay
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct LengthCounter {
+    pub counter: u8,
+    pub halt: bool,
+    enabled: bool,
+}
+
+impl LengthCounter {
+    pub fn load(&mut self, index: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(index & 0x1F) as usize];
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    pub fn clock(&mut self) {
+        // Clocked by the frame counter every half frame
+        if self.counter > 0 && !self.halt {
+            self.counter -= 1;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.counter > 0
+    }
+}
@@ -0,0 +1,218 @@
+mod dmc;
+mod envelope;
+mod noise;
+mod pulse;
+mod triangle;
+
+use dmc::Dmc;
+use noise::Noise;
+use pulse::Pulse;
+use triangle::Triangle;
+
+#[derive(Debug, Clone)]
+pub struct Apu {
+    pulse1: Pulse,
+    pulse2: Pulse,
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+
+    cycle: u64,         // CPU cycles since reset
+    frame_counter: u32, // CPU cycles into the current frame sequence
+    five_step_mode: bool,
+    irq_inhibit: bool,
+    frame_irq: bool,
+    frame_reset_delay: u8, // CPU cycles until a $4017 write resets the sequence
+}
+
+impl Default for Apu {
+    fn default() -> Self {
+        Apu::new()
+    }
+}
+
+impl Apu {
+    pub fn new() -> Self {
+        Apu {
+            pulse1: Pulse::new(true),
+            pulse2: Pulse::new(false),
+            triangle: Triangle::default(),
+            noise: Noise::new(),
+            dmc: Dmc::new(),
+            cycle: 0,
+            frame_counter: 0,
+            five_step_mode: false,
+            irq_inhibit: false,
+            frame_irq: false,
+            frame_reset_delay: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        // Reset behaves like writing $00 to $4015
+        self.write_register(0x4015, 0x00);
+        self.dmc.irq_pending = false;
+        self.cycle = 0;
+        self.frame_counter = 0;
+        // $4017 keeps its mode on reset, but the sequence restarts
+        self.frame_irq = false;
+        self.frame_reset_delay = 0;
+    }
+
+    pub fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x4000..=0x4003 => self.pulse1.write(address, value),
+            0x4004..=0x4007 => self.pulse2.write(address, value),
+            0x4008..=0x400B => self.triangle.write(address, value),
+            0x400C..=0x400F => self.noise.write(address, value),
+            0x4010..=0x4013 => self.dmc.write(address, value),
+            0x4015 => {
+                // Channel enables
+                self.pulse1.length.set_enabled(value & 0x01 != 0);
+                self.pulse2.length.set_enabled(value & 0x02 != 0);
+                self.triangle.length.set_enabled(value & 0x04 != 0);
+                self.noise.length.set_enabled(value & 0x08 != 0);
+                self.dmc.set_enabled(value & 0x10 != 0);
+            }
+            0x4017 => {
+                // Frame counter
+                self.five_step_mode = value & 0x80 != 0;
+                self.irq_inhibit = value & 0x40 != 0;
+                if self.irq_inhibit {
+                    self.frame_irq = false;
+                }
+                // The sequence resets 3 or 4 CPU cycles after the write
+                self.frame_reset_delay = if self.cycle % 2 == 1 { 4 } else { 3 };
+            }
+            _ => {}
+        }
+    }
+
+    pub fn read_status(&mut self) -> u8 {
+        // $4015: length counter and DMC status, reading acknowledges the frame IRQ
+        let mut status = 0;
+        status |= self.pulse1.length.is_active() as u8;
+        status |= (self.pulse2.length.is_active() as u8) << 1;
+        status |= (self.triangle.length.is_active() as u8) << 2;
+        status |= (self.noise.length.is_active() as u8) << 3;
+        status |= (self.dmc.is_active() as u8) << 4;
+        status |= (self.frame_irq as u8) << 6;
+        status |= (self.dmc.irq_pending as u8) << 7;
+        self.frame_irq = false;
+        status
+    }
+
+    pub fn frame_irq(&self) -> bool {
+        self.frame_irq
+    }
+
+    pub fn dmc_irq(&self) -> bool {
+        self.dmc.irq_pending
+    }
+
+    pub fn tick(&mut self) {
+        // Advance the APU by one CPU cycle
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        if self.cycle % 2 == 1 {
+            self.pulse1.clock_timer();
+            self.pulse2.clock_timer();
+        }
+        self.clock_frame_counter();
+        self.cycle += 1;
+    }
+
+    fn clock_frame_counter(&mut self) {
+        if self.frame_reset_delay > 0 {
+            self.frame_reset_delay -= 1;
+            if self.frame_reset_delay == 0 {
+                self.frame_counter = 0;
+                if self.five_step_mode {
+                    // Entering 5-step mode clocks all units immediately
+                    self.quarter_frame();
+                    self.half_frame();
+                }
+                return;
+            }
+        }
+
+        // Timings in CPU cycles
+        self.frame_counter += 1;
+        match (self.frame_counter, self.five_step_mode) {
+            (7457, _) | (22371, _) => self.quarter_frame(),
+            (14913, _) => {
+                self.quarter_frame();
+                self.half_frame();
+            }
+            (29828, false) => self.set_frame_irq(),
+            (29829, false) => {
+                self.quarter_frame();
+                self.half_frame();
+                self.set_frame_irq();
+            }
+            (29830, false) => {
+                self.set_frame_irq();
+                self.frame_counter = 0;
+            }
+            (37281, true) => {
+                self.quarter_frame();
+                self.half_frame();
+            }
+            (37282, true) => self.frame_counter = 0,
+            _ => {}
+        }
+    }
+
+    fn set_frame_irq(&mut self) {
+        if !self.irq_inhibit {
+            self.frame_irq = true;
+        }
+    }
+
+    fn quarter_frame(&mut self) {
+        self.pulse1.envelope.clock();
+        self.pulse2.envelope.clock();
+        self.noise.envelope.clock();
+        self.triangle.clock_linear_counter();
+    }
+
+    fn half_frame(&mut self) {
+        self.pulse1.length.clock();
+        self.pulse2.length.clock();
+        self.triangle.length.clock();
+        self.noise.length.clock();
+        self.pulse1.clock_sweep();
+        self.pulse2.clock_sweep();
+    }
+
+    pub fn dmc_read_request(&self) -> Option<u16> {
+        self.dmc.read_request()
+    }
+
+    pub fn dmc_fill(&mut self, value: u8) {
+        self.dmc.fill(value);
+    }
+
+    pub fn output(&self) -> f32 {
+        // Nonlinear mixer, result is in the range 0.0..1.0
+        let pulse = (self.pulse1.output() + self.pulse2.output()) as f32;
+        let pulse_out = if pulse == 0.0 {
+            0.0
+        } else {
+            95.88 / (8128.0 / pulse + 100.0)
+        };
+
+        let triangle = self.triangle.output() as f32;
+        let noise = self.noise.output() as f32;
+        let dmc = self.dmc.output() as f32;
+        let tnd = triangle / 8227.0 + noise / 12241.0 + dmc / 22638.0;
+        let tnd_out = if tnd == 0.0 {
+            0.0
+        } else {
+            159.79 / (1.0 / tnd + 100.0)
+        };
+
+        pulse_out + tnd_out
+    }
+}
@@ -0,0 +1,73 @@
+use super::envelope::{Envelope, LengthCounter};
+
+// NTSC periods in CPU cycles
+const NOISE_PERIOD_TABLE: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+
+#[derive(Debug, Clone)]
+pub struct Noise {
+    pub envelope: Envelope,
+    pub length: LengthCounter,
+    mode: bool, // Short mode feeds back from bit 6 instead of bit 1
+    shift_register: u16,
+    timer: u16,
+    timer_period: u16,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Noise::new()
+    }
+}
+
+impl Noise {
+    pub fn new() -> Self {
+        Noise {
+            envelope: Envelope::default(),
+            length: LengthCounter::default(),
+            mode: false,
+            shift_register: 1, // Loaded with 1 on power-up
+            timer: 0,
+            timer_period: NOISE_PERIOD_TABLE[0],
+        }
+    }
+
+    pub fn write(&mut self, register: u16, value: u8) {
+        match register & 0x03 {
+            0 => {
+                self.length.halt = value & 0x20 != 0;
+                self.envelope.write(value);
+            }
+            1 => {} // Unused
+            2 => {
+                self.mode = value & 0x80 != 0;
+                self.timer_period = NOISE_PERIOD_TABLE[(value & 0x0F) as usize];
+            }
+            _ => {
+                self.length.load(value >> 3);
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // Clocked every CPU cycle, the period table is in CPU cycles
+        if self.timer == 0 {
+            self.timer = self.timer_period - 1;
+            let tap = if self.mode { 6 } else { 1 };
+            let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 0x01;
+            self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.shift_register & 0x01 != 0 || !self.length.is_active() {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
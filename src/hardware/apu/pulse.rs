@@ -0,0 +1,113 @@
+use super::envelope::{Envelope, LengthCounter};
+
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+#[derive(Default, Debug, Clone)]
+pub struct Pulse {
+    pub envelope: Envelope,
+    pub length: LengthCounter,
+    ones_complement: bool, // Pulse 1 negates with one's complement
+    duty: u8,
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+    sweep_enabled: bool,
+    sweep_period: u8,
+    sweep_negate: bool,
+    sweep_shift: u8,
+    sweep_reload: bool,
+    sweep_divider: u8,
+}
+
+impl Pulse {
+    pub fn new(ones_complement: bool) -> Self {
+        Pulse {
+            ones_complement,
+            ..Default::default()
+        }
+    }
+
+    pub fn write(&mut self, register: u16, value: u8) {
+        match register & 0x03 {
+            0 => {
+                self.duty = value >> 6;
+                self.length.halt = value & 0x20 != 0;
+                self.envelope.write(value);
+            }
+            1 => {
+                self.sweep_enabled = value & 0x80 != 0;
+                self.sweep_period = (value >> 4) & 0x07;
+                self.sweep_negate = value & 0x08 != 0;
+                self.sweep_shift = value & 0x07;
+                self.sweep_reload = true;
+            }
+            2 => {
+                self.timer_period = (self.timer_period & 0x0700) | value as u16;
+            }
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | (((value & 0x07) as u16) << 8);
+                self.length.load(value >> 3);
+                self.sequence = 0;
+                self.envelope.start = true;
+            }
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // Clocked every APU cycle (every other CPU cycle)
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence = (self.sequence + 1) & 0x07;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    fn sweep_target(&self) -> u16 {
+        let change = self.timer_period >> self.sweep_shift;
+        if self.sweep_negate {
+            let target = self.timer_period.saturating_sub(change);
+            if self.ones_complement {
+                target.saturating_sub(1)
+            } else {
+                target
+            }
+        } else {
+            self.timer_period + change
+        }
+    }
+
+    fn is_muted(&self) -> bool {
+        self.timer_period < 8 || self.sweep_target() > 0x7FF
+    }
+
+    pub fn clock_sweep(&mut self) {
+        // Clocked by the frame counter every half frame
+        if self.sweep_divider == 0 && self.sweep_enabled && self.sweep_shift > 0 && !self.is_muted()
+        {
+            self.timer_period = self.sweep_target();
+        }
+        if self.sweep_divider == 0 || self.sweep_reload {
+            self.sweep_divider = self.sweep_period;
+            self.sweep_reload = false;
+        } else {
+            self.sweep_divider -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.is_muted()
+            || !self.length.is_active()
+            || DUTY_TABLE[self.duty as usize][self.sequence as usize] == 0
+        {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use super::envelope::LengthCounter;
+
+const TRIANGLE_TABLE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+    13, 14, 15,
+];
+
+#[derive(Default, Debug, Clone)]
+pub struct Triangle {
+    pub length: LengthCounter,
+    control: bool, // Also the length counter halt flag
+    linear_reload_value: u8,
+    linear_counter: u8,
+    linear_reload: bool,
+    sequence: u8,
+    timer: u16,
+    timer_period: u16,
+}
+
+impl Triangle {
+    pub fn write(&mut self, register: u16, value: u8) {
+        match register & 0x03 {
+            0 => {
+                self.control = value & 0x80 != 0;
+                self.length.halt = self.control;
+                self.linear_reload_value = value & 0x7F;
+            }
+            1 => {} // Unused
+            2 => {
+                self.timer_period = (self.timer_period & 0x0700) | value as u16;
+            }
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | (((value & 0x07) as u16) << 8);
+                self.length.load(value >> 3);
+                self.linear_reload = true;
+            }
+        }
+    }
+
+    pub fn clock_timer(&mut self) {
+        // Clocked every CPU cycle
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            if self.length.is_active() && self.linear_counter > 0 {
+                self.sequence = (self.sequence + 1) & 0x1F;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_linear_counter(&mut self) {
+        // Clocked by the frame counter every quarter frame
+        if self.linear_reload {
+            self.linear_counter = self.linear_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control {
+            self.linear_reload = false;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        // The sequencer just stops when silenced, so the last value keeps being output
+        TRIANGLE_TABLE[self.sequence as usize]
+    }
+}
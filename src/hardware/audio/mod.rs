@@ -0,0 +1,28 @@
+mod resampler;
+mod ring_buffer;
+mod wav;
+
+use std::io;
+
+pub use resampler::Resampler;
+pub use ring_buffer::{AudioBuffer, RingBufferSink};
+pub use wav::WavSink;
+
+// The APU produces one sample per CPU cycle (NTSC)
+pub const APU_SAMPLE_RATE: f64 = 1_789_773.0;
+
+pub trait AudioSink {
+    fn sample_rate(&self) -> u32;
+
+    fn write_sample(&mut self, sample: f32);
+
+    // Factor applied to the resampling step, above 1.0 produces fewer samples.
+    // Real-time sinks use it to keep their buffer from starving or overflowing.
+    fn rate_adjustment(&self) -> f64 {
+        1.0
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+// Box-filter decimation before the sinc stage keeps the FIR short
+const DECIMATION: u32 = 8;
+// Sinc zero crossings on each side of the kernel centre
+const ZERO_CROSSINGS: f64 = 8.0;
+// Kernel table resolution between two input samples
+const PHASES: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    step: f64, // Intermediate samples per output sample
+    adjustment: f64,
+    half_width: usize,
+    kernel: Vec<f32>,
+
+    accumulator: f32,
+    accumulated: u32,
+    history: VecDeque<f32>,
+    position: f64, // Time of the next output sample, relative to history[0]
+
+    dc_last_input: f32,
+    dc_last_output: f32,
+}
+
+impl Resampler {
+    pub fn new(input_rate: f64, output_rate: f64) -> Self {
+        let intermediate_rate = input_rate / DECIMATION as f64;
+        let step = intermediate_rate / output_rate;
+
+        // Low-pass a little below the output Nyquist frequency
+        let cutoff = (0.45 / step).min(0.5);
+        let half_width = (ZERO_CROSSINGS / (2.0 * cutoff)).ceil() as usize;
+        let kernel = (0..=2 * half_width * PHASES)
+            .map(|i| {
+                let u = i as f64 / PHASES as f64 - half_width as f64;
+                let x = 2.0 * cutoff * u;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * x).sin() / (PI * x)
+                };
+                // Blackman window
+                let w = (u / half_width as f64 + 1.0) / 2.0;
+                let window = 0.42 - 0.5 * (2.0 * PI * w).cos() + 0.08 * (4.0 * PI * w).cos();
+                (2.0 * cutoff * sinc * window) as f32
+            })
+            .collect();
+
+        Resampler {
+            step,
+            adjustment: 1.0,
+            half_width,
+            kernel,
+            accumulator: 0.0,
+            accumulated: 0,
+            history: VecDeque::with_capacity(4 * half_width),
+            position: half_width as f64,
+            dc_last_input: 0.0,
+            dc_last_output: 0.0,
+        }
+    }
+
+    pub fn set_adjustment(&mut self, adjustment: f64) {
+        self.adjustment = adjustment;
+    }
+
+    pub fn push(&mut self, sample: f32, mut output: impl FnMut(f32)) {
+        self.accumulator += sample;
+        self.accumulated += 1;
+        if self.accumulated < DECIMATION {
+            return;
+        }
+        self.history.push_back(self.accumulator / DECIMATION as f32);
+        self.accumulator = 0.0;
+        self.accumulated = 0;
+
+        // Emit every output sample whose kernel window is fully available
+        while self.position + (self.half_width as f64) < self.history.len() as f64 {
+            let value = self.convolve();
+            output(self.remove_dc(value));
+            self.position += self.step * self.adjustment;
+        }
+
+        // Drop history that no future output sample can reach
+        while self.position - self.half_width as f64 > 1.0 {
+            self.history.pop_front();
+            self.position -= 1.0;
+        }
+    }
+
+    fn convolve(&self) -> f32 {
+        let first = (self.position - self.half_width as f64).ceil().max(0.0) as usize;
+        let last = (self.position + self.half_width as f64).floor() as usize;
+        let mut sum = 0.0;
+        for index in first..=last.min(self.history.len() - 1) {
+            let u = index as f64 - self.position + self.half_width as f64;
+            let table_pos = u * PHASES as f64;
+            let i = table_pos as usize;
+            let frac = (table_pos - i as f64) as f32;
+            let a = self.kernel[i];
+            let b = self.kernel.get(i + 1).copied().unwrap_or(0.0);
+            sum += self.history[index] * (a + (b - a) * frac);
+        }
+        sum
+    }
+
+    fn remove_dc(&mut self, value: f32) -> f32 {
+        // One-pole high-pass, the mixer output is always positive
+        let output = value - self.dc_last_input + 0.995 * self.dc_last_output;
+        self.dc_last_input = value;
+        self.dc_last_output = output;
+        output
+    }
+}
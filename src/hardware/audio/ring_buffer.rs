@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::AudioSink;
+
+// Maximum deviation from the nominal rate used to steer the buffer level
+const MAX_RATE_DELTA: f64 = 0.005;
+
+// Consumer side of a RingBufferSink, cheap to clone and share with the UI
+#[derive(Debug, Clone)]
+pub struct AudioBuffer {
+    samples: Arc<Mutex<VecDeque<f32>>>,
+    capacity: usize,
+    level: Arc<AtomicU32>, // RMS of the last read, as f32 bits
+}
+
+impl AudioBuffer {
+    pub fn read(&self, out: &mut [f32]) -> usize {
+        // Fills `out` from the oldest samples without allocating, so it can run in an
+        // audio callback. Returns how many were available, the rest of `out` is untouched
+        let mut samples = self.samples.lock().unwrap();
+        let count = out.len().min(samples.len());
+        let mut sum = 0.0;
+        for (slot, sample) in out.iter_mut().zip(samples.drain(..count)) {
+            *slot = sample;
+            sum += sample * sample;
+        }
+        if count > 0 {
+            let rms = (sum / count as f32).sqrt();
+            self.level.store(rms.to_bits(), Ordering::Relaxed);
+        }
+        count
+    }
+
+    pub fn level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.lock().unwrap().len()
+    }
+}
+
+#[derive(Debug)]
+pub struct RingBufferSink {
+    buffer: AudioBuffer,
+    sample_rate: u32,
+}
+
+impl RingBufferSink {
+    pub fn new(sample_rate: u32, capacity: usize) -> Self {
+        RingBufferSink {
+            buffer: AudioBuffer {
+                samples: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+                capacity,
+                level: Arc::new(AtomicU32::new(0)),
+            },
+            sample_rate,
+        }
+    }
+
+    pub fn buffer(&self) -> AudioBuffer {
+        self.buffer.clone()
+    }
+}
+
+impl AudioSink for RingBufferSink {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn write_sample(&mut self, sample: f32) {
+        let mut samples = self.buffer.samples.lock().unwrap();
+        if samples.len() >= self.buffer.capacity {
+            // Consumer fell behind, drop the oldest sample
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    fn rate_adjustment(&self) -> f64 {
+        // Aim for a half-full buffer: produce less when fuller, more when emptier
+        let target = self.buffer.capacity as f64 / 2.0;
+        let fill = self.buffer.len() as f64;
+        1.0 + MAX_RATE_DELTA * ((fill - target) / target).clamp(-1.0, 1.0)
+    }
+}
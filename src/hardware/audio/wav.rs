@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+
+use super::AudioSink;
+
+// 16-bit mono PCM WAV file
+#[derive(Debug)]
+pub struct WavSink {
+    writer: BufWriter<File>,
+    sample_rate: u32,
+    data_size: u32,
+}
+
+impl WavSink {
+    pub fn create(file_path: &str, sample_rate: u32) -> Result<Self, io::Error> {
+        let mut sink = WavSink {
+            writer: BufWriter::new(File::create(file_path)?),
+            sample_rate,
+            data_size: 0,
+        };
+        sink.write_header()?;
+        Ok(sink)
+    }
+
+    fn write_header(&mut self) -> Result<(), io::Error> {
+        let byte_rate = self.sample_rate * 2;
+        let w = &mut self.writer;
+        w.write_all(b"RIFF")?;
+        w.write_all(&(36 + self.data_size).to_le_bytes())?;
+        w.write_all(b"WAVE")?;
+        w.write_all(b"fmt ")?;
+        w.write_all(&16u32.to_le_bytes())?; // fmt chunk size
+        w.write_all(&1u16.to_le_bytes())?; // PCM
+        w.write_all(&1u16.to_le_bytes())?; // Mono
+        w.write_all(&self.sample_rate.to_le_bytes())?;
+        w.write_all(&byte_rate.to_le_bytes())?;
+        w.write_all(&2u16.to_le_bytes())?; // Block align
+        w.write_all(&16u16.to_le_bytes())?; // Bits per sample
+        w.write_all(b"data")?;
+        w.write_all(&self.data_size.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+impl AudioSink for WavSink {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn write_sample(&mut self, sample: f32) {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        if self.writer.write_all(&value.to_le_bytes()).is_ok() {
+            self.data_size += 2;
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Patch the chunk sizes so the file is valid even if recording continues
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()
+    }
+}
+
+impl Drop for WavSink {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            eprintln!("Error finalizing WAV file: {}", err);
+        }
+    }
+}
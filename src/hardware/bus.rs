@@ -1,26 +1,30 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use super::apu::Apu;
 use super::cpu::opcode;
 use super::enums::Registers;
+use super::interrupts::{Interrupts, IrqSource};
 use super::ppu::Ppu;
 use super::{
     cartridge::Cartridge,
+    controller::Controller,
     cpu::{CPU, instructions::AddressMode},
     memory::Memory,
 };
-pub struct ReadAddressWithModeResult {
-    pub value: u8,
-    pub address: u16,
-    pub cycles: u8,
-}
-
 #[derive(Debug, Clone)]
 pub struct Bus {
     pub cpu: CPU,
     pub memory: Rc<RefCell<Memory>>,
     pub ppu: Ppu,
+    pub apu: Apu,
     pub cartridge: Rc<RefCell<Cartridge>>,
+    pub interrupts: Interrupts,
+    pub oam_dma_page: Option<u8>, // Set by a $4014 write, copied after the instruction
+    pub controllers: [Controller; 2],
+    pub cycles: u64,             // CPU cycles since power on, stalls included
+    pub stall_cycles: u64,       // Cycles the DMC and OAM DMA took from the CPU
+    pub audio_samples: Vec<f32>, // One mixed sample per CPU cycle, drained by Hardware
 }
 
 impl Bus {
@@ -28,31 +32,24 @@ impl Bus {
         let cartridge = Rc::new(RefCell::new(Cartridge::new()));
         let memory = Rc::new(RefCell::new(Memory::new()));
         let bus = Bus {
-            cpu: CPU::new(memory.clone()),
-            memory: memory.clone(),
+            cpu: CPU::new(),
+            memory,
             ppu: Ppu::new(Rc::clone(&cartridge)),
+            apu: Apu::new(),
             cartridge: Rc::clone(&cartridge),
+            interrupts: Interrupts::new(),
+            oam_dma_page: None,
+            controllers: [Controller::new(), Controller::new()],
+            cycles: 0,
+            stall_cycles: 0,
+            audio_samples: Vec::new(),
         };
         return bus;
     }
 
     pub fn increment_pc(&mut self, address_mode: &AddressMode) {
         let pc = self.cpu.get_counter();
-        let value = match address_mode {
-            AddressMode::Immediate => 2,
-            AddressMode::IndirectX => 2,
-            AddressMode::IndirectY => 2,
-            AddressMode::Indirect => 3,
-            AddressMode::ZeroPage => 2,
-            AddressMode::ZeroPageX => 2,
-            AddressMode::ZeroPageY => 2,
-            AddressMode::Absolute => 3,
-            AddressMode::AbsoluteX => 3,
-            AddressMode::AbsoluteY => 3,
-            AddressMode::Implicit => 1,
-            AddressMode::Accumulator => 1,
-            AddressMode::Relative => 2,
-        };
+        let value = address_mode.instruction_len() as u16;
         let new_pc = pc.wrapping_add(value);
         self.cpu.set_counter(new_pc);
     }
@@ -62,33 +59,80 @@ impl Bus {
         self.cpu.set_counter(pc + value);
     }
 
+    pub fn tick(&mut self) {
+        // A CPU cycle without a bus access of its own
+        self.clock();
+        if let Some(address) = self.apu.dmc_read_request() {
+            // CPU is stalled while the DMC fetches its next sample byte
+            for _ in 0..4 {
+                self.clock();
+            }
+            self.stall_cycles += 4;
+            let value = self.bus_read(address);
+            self.apu.dmc_fill(value);
+        }
+    }
+
+    fn clock(&mut self) {
+        // Runs the rest of the console for one CPU cycle
+        self.poll_interrupts();
+        self.cycles += 1;
+        for _ in 0..3 {
+            self.ppu.tick();
+        }
+        self.apu.tick();
+        let expansion_audio = {
+            let mut cartridge = self.cartridge.borrow_mut();
+            cartridge.mapper.cpu_tick();
+            cartridge.mapper.audio_output()
+        };
+        self.audio_samples.push(self.apu.output() + expansion_audio);
+    }
+
     pub fn read(&mut self, address: u16) -> u8 {
+        // One CPU read cycle, the PPU and APU catch up before the access
+        self.tick();
+        self.bus_read(address)
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        // One CPU write cycle
+        self.tick();
+        self.bus_write(address, value);
+    }
+
+    fn bus_read(&mut self, address: u16) -> u8 {
         if address < 0x2000 {
             self.memory.borrow().read(address)
         } else if address < 0x4000 {
             self.ppu
                 .read_register(address)
                 .expect("Could not read PPU register")
+        } else if address == 0x4016 {
+            self.controllers[0].read()
+        } else if address == 0x4017 {
+            self.controllers[1].read()
+        } else if address == 0x4015 {
+            self.apu.read_status()
         } else if address < 0x4018 {
-            // APU and I/O registers - Implement later
+            // Remaining APU registers are write-only
             0
         } else if address < 0x4020 {
             // Normally disabled
             0
         } else {
-            // Cartridge memory
-            self.cartridge.borrow().mapper.cpu_read(address).expect(
-                format!("Could not read from cartridge at address {:#04x}", address).as_str(),
-            )
+            // Cartridge memory, anything the board leaves unmapped is open bus
+            self.cartridge
+                .borrow()
+                .mapper
+                .cpu_read(address)
+                .unwrap_or((address >> 8) as u8)
         }
     }
 
     pub fn read_cartridge(&self, address: u16) -> u8 {
-        self.cartridge
-            .borrow()
-            .mapper
-            .cpu_read(address)
-            .unwrap_or(0)
+        // Debugger reads must not disturb the board
+        self.cartridge.borrow().mapper.peek(address).unwrap_or(0)
     }
 
     pub fn read_instruct(&mut self) -> u8 {
@@ -122,21 +166,60 @@ impl Bus {
         self.read_word(pc.wrapping_add(1))
     }
 
-    pub fn write(&mut self, address: u16, value: u8) {
+    fn bus_write(&mut self, address: u16, value: u8) {
         if address < 0x2000 {
             self.memory.borrow_mut().write(address, value);
         } else if address < 0x4000 {
             self.ppu.write_register(address, value);
+        } else if address == 0x4014 {
+            self.oam_dma_page = Some(value);
+        } else if address == 0x4016 {
+            // Strobe is shared by both controller ports
+            self.controllers[0].write(value);
+            self.controllers[1].write(value);
         } else if address < 0x4018 {
-            // APU and I/O registers - Implement later
+            self.apu.write_register(address, value);
         } else if address < 0x4020 {
             // Normally disabled
         } else {
             // Cartridge memory
-            self.cartridge.borrow_mut().mapper.cpu_write(address, value);
+            self.cartridge.borrow_mut().cpu_write(address, value);
         }
     }
 
+    pub fn run_oam_dma(&mut self) {
+        // Copy $XX00-$XXFF into OAM, the same way 256 writes to OAMDATA would.
+        // 513 cycles, plus one more to align when the transfer starts on an odd cycle
+        let Some(page) = self.oam_dma_page.take() else {
+            return;
+        };
+        let start = self.cycles;
+        self.tick();
+        if !self.cycles.is_multiple_of(2) {
+            self.tick();
+        }
+        let base = (page as u16) << 8;
+        for offset in 0..256 {
+            let value = self.read(base + offset);
+            self.tick();
+            let oam_addr = self.ppu.oam_addr;
+            self.ppu.oam_data[oam_addr as usize] = value;
+            self.ppu.oam_addr = oam_addr.wrapping_add(1);
+        }
+        self.stall_cycles += self.cycles - start;
+    }
+
+    fn poll_interrupts(&mut self) {
+        // Samples the lines as the previous cycle left them
+        let mapper_irq = self.cartridge.borrow().mapper.irq();
+        self.interrupts
+            .set_irq(IrqSource::FrameCounter, self.apu.frame_irq());
+        self.interrupts.set_irq(IrqSource::Dmc, self.apu.dmc_irq());
+        self.interrupts.set_irq(IrqSource::Mapper, mapper_irq);
+        self.interrupts
+            .poll(self.ppu.nmi_line(), self.cpu.get_interrupt_disable() != 0);
+    }
+
     pub fn stack_push(&mut self, value: u8) {
         let sp = self.cpu.get(Registers::S);
         self.write(0x100 + sp as u16, value);
@@ -162,161 +245,124 @@ impl Bus {
         ((high_byte as u16) << 8) + (low_byte as u16)
     }
 
-    pub fn read_address_with_mode(
-        &mut self,
-        address_mode: &AddressMode,
-    ) -> ReadAddressWithModeResult {
-        "
-        Reads the value from memory based on the address mode and operand.
-        args:
-            address_mode: The addressing mode to use for reading.
-            operand: The operand to use in conjunction with the address mode.
-        returns:
-            A tuple containing the value read from memory and the address it was read from.
-        ";
+    pub fn operand_address(&mut self, address_mode: &AddressMode, write: bool) -> (u16, bool) {
+        // Fetches the operand of the instruction at PC and resolves its effective
+        // address, one bus access per cycle. Returns whether indexing crossed a
+        // page. Reads only spend the fix-up cycle on a page cross, writes always do
         match address_mode {
-            AddressMode::Implicit => {
-                return ReadAddressWithModeResult {
-                    value: 0,
-                    address: 0,
-                    cycles: 0,
+            AddressMode::ZeroPage => (self.read_next() as u16, false),
+            AddressMode::ZeroPageX | AddressMode::ZeroPageY => {
+                let base = self.read_next();
+                let index = match address_mode {
+                    AddressMode::ZeroPageX => self.cpu.get(Registers::X),
+                    _ => self.cpu.get(Registers::Y),
                 };
+                self.read(base as u16); // Dummy read while the index is added
+                (base.wrapping_add(index) as u16, false)
             }
-            AddressMode::Immediate => ReadAddressWithModeResult {
-                value: self.read_next(),
-                address: 0,
-                cycles: 0,
-            },
-
-            AddressMode::Relative => {
-                let relative = self.read_next() as i8;
-                let pc = (self.cpu.get_counter()).wrapping_add_signed(relative as i16);
-                ReadAddressWithModeResult {
-                    value: 0,
-                    address: pc as u16,
-                    cycles: 0,
-                }
+            AddressMode::Absolute => (self.read_next_word(), false),
+            AddressMode::AbsoluteX | AddressMode::AbsoluteY => {
+                let base = self.read_next_word();
+                let index = match address_mode {
+                    AddressMode::AbsoluteX => self.cpu.get(Registers::X),
+                    _ => self.cpu.get(Registers::Y),
+                };
+                let address = base.wrapping_add(index as u16);
+                let crossed = self.fix_page(base, address, write);
+                (address, crossed)
             }
-
-            AddressMode::Accumulator => ReadAddressWithModeResult {
-                value: self.cpu.get(Registers::A),
-                address: 0,
-                cycles: 0,
-            },
-
             AddressMode::Indirect => {
-                let pointer: u16 = self.read_next_word();
-                let address = self.read_word_buggy(pointer);
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles: 0,
-                }
+                // Only JMP, the pointer never leaves its page
+                let pointer = self.read_next_word();
+                (self.read_word_buggy(pointer), false)
             }
-
             AddressMode::IndirectX => {
-                let operand = self.read_next();
-                let zero_page_pointer = operand.wrapping_add(self.cpu.get(Registers::X));
-                // We don't use read_word because we have to wrap into zero page
-                // when reading high byte. read_word first converts the address to
-                // u16 then adds 1 to it, which is not what we want here.
-                let low_byte = self.read(zero_page_pointer as u16);
-                let high_byte = self.read(zero_page_pointer.wrapping_add(1) as u16);
-                let address: u16 = ((high_byte as u16) << 8) + (low_byte as u16);
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles: 0,
-                }
+                let base = self.read_next();
+                self.read(base as u16); // Dummy read while X is added
+                // The pointer wraps within zero page
+                let pointer = base.wrapping_add(self.cpu.get(Registers::X));
+                let low_byte = self.read(pointer as u16);
+                let high_byte = self.read(pointer.wrapping_add(1) as u16);
+                (((high_byte as u16) << 8) | low_byte as u16, false)
             }
-
             AddressMode::IndirectY => {
-                let operand = self.read_next() as u16;
-                let zero_page_pointer = self.read_word(operand);
-                let address = zero_page_pointer.wrapping_add(self.cpu.get(Registers::Y) as u16);
-                let crossed_page = (zero_page_pointer & 0xFF00) != (address & 0xFF00);
-                let mut cycles = 0;
-                if crossed_page {
-                    cycles = 1;
-                }
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles,
-                }
+                let pointer = self.read_next();
+                let low_byte = self.read(pointer as u16);
+                let high_byte = self.read(pointer.wrapping_add(1) as u16);
+                let base = ((high_byte as u16) << 8) | low_byte as u16;
+                let address = base.wrapping_add(self.cpu.get(Registers::Y) as u16);
+                let crossed = self.fix_page(base, address, write);
+                (address, crossed)
             }
-
-            AddressMode::Absolute => {
-                let address = self.read_next_word();
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles: 0,
-                }
+            AddressMode::Implicit
+            | AddressMode::Accumulator
+            | AddressMode::Immediate
+            | AddressMode::Relative => {
+                panic!("{} address mode has no effective address", address_mode)
             }
+        }
+    }
 
-            AddressMode::AbsoluteX => {
-                let operand = self.read_next_word();
-                let address = operand.wrapping_add(self.cpu.get(Registers::X) as u16);
-                let crossed_page = (operand & 0xFF00) != (address & 0xFF00);
-                let mut cycles = 0;
-                if crossed_page {
-                    cycles = 1;
-                }
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles,
-                }
-            }
+    fn fix_page(&mut self, base: u16, address: u16, write: bool) -> bool {
+        // Indexing adds to the low byte first, so the CPU reads from the
+        // un-fixed address before the carry reaches the high byte. Reads
+        // that stay on the page use that value and skip the extra cycle
+        let crossed = (base & 0xFF00) != (address & 0xFF00);
+        if crossed || write {
+            self.read((base & 0xFF00) | (address & 0x00FF));
+        }
+        crossed
+    }
 
-            AddressMode::AbsoluteY => {
-                let operand = self.read_next_word();
-                let address = operand.wrapping_add(self.cpu.get(Registers::Y) as u16);
-                let crossed_page = (operand & 0xFF00) != (address & 0xFF00);
-                let mut cycles = 0;
-                if crossed_page {
-                    cycles = 1;
-                }
-                ReadAddressWithModeResult {
-                    value: self.memory.borrow().read(address),
-                    address,
-                    cycles,
-                }
-            }
+    pub fn dummy_read(&mut self) {
+        // Single byte instructions still read the byte after the opcode
+        self.read_next();
+    }
 
-            AddressMode::ZeroPage => {
-                let address = self.read_next() as u16;
-                let value = self.read(address);
-                ReadAddressWithModeResult {
-                    value,
-                    address,
-                    cycles: 0,
-                }
+    pub fn read_operand(&mut self, address_mode: &AddressMode) -> u8 {
+        // Value read by load, arithmetic and compare instructions
+        match address_mode {
+            AddressMode::Immediate => self.read_next(),
+            AddressMode::Implicit => {
+                self.dummy_read();
+                0
             }
-
-            AddressMode::ZeroPageX => {
-                let operand = self.read_next();
-                let address = operand.wrapping_add(self.cpu.get(Registers::X)) as u16;
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles: 0,
-                }
+            AddressMode::Accumulator => {
+                self.dummy_read();
+                self.cpu.get(Registers::A)
             }
-
-            AddressMode::ZeroPageY => {
-                let operand = self.read_next();
-                let address = operand.wrapping_add(self.cpu.get(Registers::Y)) as u16;
-                ReadAddressWithModeResult {
-                    value: self.read(address),
-                    address,
-                    cycles: 0,
-                }
+            _ => {
+                let (address, _) = self.operand_address(address_mode, false);
+                self.read(address)
             }
         }
     }
 
+    pub fn write_operand(&mut self, address_mode: &AddressMode, value: u8) {
+        let (address, _) = self.operand_address(address_mode, true);
+        self.write(address, value);
+    }
+
+    pub fn modify_operand(
+        &mut self,
+        address_mode: &AddressMode,
+        modify: impl FnOnce(&mut CPU, u8) -> u8,
+    ) {
+        // Read-modify-write: read, write the old value while modifying, then the result
+        if *address_mode == AddressMode::Accumulator {
+            self.dummy_read();
+            let value = self.cpu.get(Registers::A);
+            let result = modify(&mut self.cpu, value);
+            self.cpu.set(Registers::A, result);
+            return;
+        }
+        let (address, _) = self.operand_address(address_mode, true);
+        let value = self.read(address);
+        self.write(address, value); // The unmodified value is written back first
+        let result = modify(&mut self.cpu, value);
+        self.write(address, result);
+    }
+
     pub fn ppu_read(&mut self, address: u16) -> Option<u8> {
         match address {
             0x0000..=0x1FFF => {
@@ -369,7 +415,10 @@ impl Bus {
         self.cpu.reset();
         self.memory.borrow_mut().reset();
         self.ppu.reset();
+        self.apu.reset();
         self.cartridge.borrow_mut().reset();
+        self.interrupts.reset();
+        self.oam_dma_page = None;
         let reset_vector = self.read_word(0xFFFC);
         self.cpu.set_counter(reset_vector);
     }
@@ -377,28 +426,15 @@ impl Bus {
     pub fn create_disassembled_line(&self, address: u16) -> (String, u8) {
         let opcode = self.read_cartridge(address);
         let instruction = opcode::get_instruction(opcode);
-        let disassembled;
-        let instruction_len: u8;
-        if let Some(instruction) = instruction {
-            let (instruct, len) =
-                self.get_instruction_text(&instruction.address_mode, Some(address));
-            disassembled = format!(
-                "{0:04X}: {1} [ {2} {3} ]",
-                address, instruct, instruction.name, instruction.address_mode
-            );
-            instruction_len = len;
-        } else {
-            disassembled = format!("{:04X}: {:02X} <unknown>", address, opcode);
-            instruction_len = 1;
-        }
-        (disassembled, instruction_len)
+        let instruct = self.get_instruction_text(&instruction.address_mode, Some(address));
+        let disassembled = format!(
+            "{0:04X}: {1} [ {2} {3} ]",
+            address, instruct, instruction.name, instruction.address_mode
+        );
+        (disassembled, instruction.bytes)
     }
 
-    pub fn get_instruction_text(
-        &self,
-        address_mode: &AddressMode,
-        address: Option<u16>,
-    ) -> (String, u8) {
+    pub fn get_instruction_text(&self, address_mode: &AddressMode, address: Option<u16>) -> String {
         let next: u8;
         let next_word: u16;
         let instruct: u8;
@@ -414,21 +450,20 @@ impl Bus {
                 | self.read_cartridge(self.cpu.get_counter().wrapping_add(2)) as u16;
         }
 
-        let (instruct, instruct_len) = match address_mode {
-            AddressMode::Implicit => (format!("{0:x}", instruct), 1),
-            AddressMode::Immediate => (format!("{0:x} #${1:02X}", instruct, next), 2),
-            AddressMode::Accumulator => (format!("{0:x}", instruct), 1),
-            AddressMode::ZeroPage => (format!("{0:x} ${1:02X}", instruct, next), 2),
-            AddressMode::ZeroPageX => (format!("{0:x} ${1:02X}", instruct, next), 2),
-            AddressMode::ZeroPageY => (format!("{0:x} ${1:02X}, Y", instruct, next), 2),
-            AddressMode::Absolute => (format!("{0:x} ${1:04X}", instruct, next_word), 3),
-            AddressMode::AbsoluteX => (format!("{0:x} ${1:04X}, X", instruct, next_word), 3),
-            AddressMode::AbsoluteY => (format!("{0:x} ${1:04X}, Y", instruct, next_word), 3),
-            AddressMode::Relative => (format!("{0:x} ${1:02X}", instruct, next), 2),
-            AddressMode::Indirect => (format!("{0:x} (${1:04X})", instruct, next_word), 3),
-            AddressMode::IndirectX => (format!("{0:x} (${1:02X}, X)", instruct, next), 2),
-            AddressMode::IndirectY => (format!("{0:x} (${1:02X}), Y", instruct, next), 2),
-        };
-        (instruct, instruct_len)
+        match address_mode {
+            AddressMode::Implicit => format!("{0:x}", instruct),
+            AddressMode::Immediate => format!("{0:x} #${1:02X}", instruct, next),
+            AddressMode::Accumulator => format!("{0:x}", instruct),
+            AddressMode::ZeroPage => format!("{0:x} ${1:02X}", instruct, next),
+            AddressMode::ZeroPageX => format!("{0:x} ${1:02X}", instruct, next),
+            AddressMode::ZeroPageY => format!("{0:x} ${1:02X}, Y", instruct, next),
+            AddressMode::Absolute => format!("{0:x} ${1:04X}", instruct, next_word),
+            AddressMode::AbsoluteX => format!("{0:x} ${1:04X}, X", instruct, next_word),
+            AddressMode::AbsoluteY => format!("{0:x} ${1:04X}, Y", instruct, next_word),
+            AddressMode::Relative => format!("{0:x} ${1:02X}", instruct, next),
+            AddressMode::Indirect => format!("{0:x} (${1:04X})", instruct, next_word),
+            AddressMode::IndirectX => format!("{0:x} (${1:02X}, X)", instruct, next),
+            AddressMode::IndirectY => format!("{0:x} (${1:02X}), Y", instruct, next),
+        }
     }
 }
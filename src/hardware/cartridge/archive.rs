@@ -0,0 +1,95 @@
+use super::CartridgeError;
+use flate2::read::{DeflateDecoder, GzDecoder};
+use std::borrow::Cow;
+use std::io::Read;
+
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+const ZIP_LOCAL_HEADER: u32 = 0x04034B50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014B50;
+const ZIP_END_OF_DIRECTORY: u32 = 0x06054B50;
+const ROM_EXTENSIONS: &[&[u8]] = &[b".nes", b".unf", b".unif", b".fds"];
+
+// Unpack .gz and .zip containers, anything else is returned as is
+pub fn unpack(data: &[u8]) -> Result<Cow<'_, [u8]>, CartridgeError> {
+    if data.starts_with(GZIP_MAGIC) {
+        let mut rom = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut rom)?;
+        Ok(Cow::Owned(rom))
+    } else if read_u32(data, 0) == Some(ZIP_LOCAL_HEADER) {
+        unpack_zip(data).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(data))
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn unpack_zip(data: &[u8]) -> Result<Vec<u8>, CartridgeError> {
+    let invalid = |reason| CartridgeError::InvalidArchive { reason };
+
+    // The end of central directory record sits in the last 64KB + 22 bytes
+    let search_start = data.len().saturating_sub(0xFFFF + 22);
+    let end = (search_start..data.len().saturating_sub(21))
+        .rev()
+        .find(|&offset| read_u32(data, offset) == Some(ZIP_END_OF_DIRECTORY))
+        .ok_or(invalid("missing end of central directory"))?;
+    let entries = read_u16(data, end + 10).ok_or(invalid("truncated directory"))?;
+    let mut offset = read_u32(data, end + 16).ok_or(invalid("truncated directory"))? as usize;
+
+    // Sizes in local headers can be zero when a data descriptor is used,
+    // so take them from the central directory instead
+    for _ in 0..entries {
+        if read_u32(data, offset) != Some(ZIP_CENTRAL_HEADER) {
+            return Err(invalid("bad central directory entry"));
+        }
+        let field = |at| read_u16(data, offset + at).ok_or(invalid("truncated directory"));
+        let method = field(10)?;
+        let name_len = field(28)? as usize;
+        let extra_len = field(30)? as usize;
+        let comment_len = field(32)? as usize;
+        let compressed_size =
+            read_u32(data, offset + 20).ok_or(invalid("truncated directory"))? as usize;
+        let local_offset =
+            read_u32(data, offset + 42).ok_or(invalid("truncated directory"))? as usize;
+        let name = data
+            .get(offset + 46..offset + 46 + name_len)
+            .ok_or(invalid("truncated directory"))?;
+        offset += 46 + name_len + extra_len + comment_len;
+
+        let name = name.to_ascii_lowercase();
+        if !ROM_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+            continue;
+        }
+
+        if read_u32(data, local_offset) != Some(ZIP_LOCAL_HEADER) {
+            return Err(invalid("bad local file header"));
+        }
+        let local_name_len =
+            read_u16(data, local_offset + 26).ok_or(invalid("truncated entry"))? as usize;
+        let local_extra_len =
+            read_u16(data, local_offset + 28).ok_or(invalid("truncated entry"))? as usize;
+        let start = local_offset + 30 + local_name_len + local_extra_len;
+        let compressed = data
+            .get(start..start + compressed_size)
+            .ok_or(invalid("truncated entry"))?;
+
+        return match method {
+            0 => Ok(compressed.to_vec()), // Stored
+            8 => {
+                let mut rom = Vec::new();
+                DeflateDecoder::new(compressed).read_to_end(&mut rom)?;
+                Ok(rom)
+            }
+            _ => Err(invalid("unsupported compression method")),
+        };
+    }
+    Err(CartridgeError::NoRomInArchive)
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RomSection {
+    Header,
+    Trainer,
+    PrgRom,
+    ChrRom,
+    UnifChunk([u8; 4]),
+    DiskSide(usize),
+}
+
+#[derive(Debug)]
+pub enum CartridgeError {
+    Io(io::Error),
+    BadMagic([u8; 4]),
+    Truncated {
+        section: RomSection,
+        expected: usize,
+        got: usize,
+    },
+    UnsupportedMapper {
+        id: u16,
+        name: Option<&'static str>,
+    },
+    InvalidArchive {
+        reason: &'static str,
+    },
+    NoRomInArchive,
+    UnsupportedBoard(String),
+    MissingFdsBios,
+    InvalidFdsBios {
+        size: usize,
+    },
+}
+
+impl fmt::Display for RomSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RomSection::Header => write!(f, "header"),
+            RomSection::Trainer => write!(f, "trainer"),
+            RomSection::PrgRom => write!(f, "PRG ROM"),
+            RomSection::ChrRom => write!(f, "CHR ROM"),
+            RomSection::UnifChunk(id) => write!(f, "UNIF chunk {}", String::from_utf8_lossy(id)),
+            RomSection::DiskSide(side) => write!(f, "disk side {}", side + 1),
+        }
+    }
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartridgeError::Io(err) => write!(f, "Could not read ROM: {}", err),
+            CartridgeError::BadMagic(magic) => {
+                write!(f, "Not an iNES, UNIF or FDS image (magic {:02X?})", magic)
+            }
+            CartridgeError::Truncated {
+                section,
+                expected,
+                got,
+            } => write!(
+                f,
+                "ROM is truncated: {} needs {} bytes but only {} are left",
+                section, expected, got
+            ),
+            CartridgeError::UnsupportedMapper {
+                id,
+                name: Some(name),
+            } => {
+                write!(f, "Unsupported mapper {} ({})", id, name)
+            }
+            CartridgeError::UnsupportedMapper { id, name: None } => {
+                write!(f, "Unsupported mapper {}", id)
+            }
+            CartridgeError::InvalidArchive { reason } => {
+                write!(f, "Invalid zip archive: {}", reason)
+            }
+            CartridgeError::NoRomInArchive => {
+                write!(f, "Archive contains no .nes, .unf or .fds file")
+            }
+            CartridgeError::UnsupportedBoard(board) => {
+                write!(f, "Unsupported UNIF board {}", board)
+            }
+            CartridgeError::MissingFdsBios => write!(
+                f,
+                "Disk images need the FDS BIOS, put disksys.rom next to the image"
+            ),
+            CartridgeError::InvalidFdsBios { size } => {
+                write!(f, "FDS BIOS must be 8192 bytes, got {}", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CartridgeError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CartridgeError {
+    fn from(err: io::Error) -> Self {
+        CartridgeError::Io(err)
+    }
+}
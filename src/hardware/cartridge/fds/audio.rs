@@ -0,0 +1,215 @@
+// Modulation table entries, 4 resets the counter instead of adding
+const MOD_STEPS: [i32; 8] = [0, 1, 2, 4, 0, -4, -2, -1];
+const MASTER_VOLUME: [f32; 4] = [1.0, 2.0 / 3.0, 0.5, 0.4];
+// At full volume the FDS is about 2.4 times as loud as one pulse channel
+const OUTPUT_LEVEL: f32 = 0.36;
+
+#[derive(Debug, Clone, Default)]
+struct Envelope {
+    speed: u8,
+    increase: bool,
+    disabled: bool,
+    gain: u8,
+    timer: u32,
+}
+
+impl Envelope {
+    fn write(&mut self, value: u8, master_speed: u8) {
+        self.speed = value & 0x3F;
+        self.increase = value & 0x40 != 0;
+        self.disabled = value & 0x80 != 0;
+        if self.disabled {
+            self.gain = self.speed;
+        }
+        self.reset_timer(master_speed);
+    }
+
+    fn reset_timer(&mut self, master_speed: u8) {
+        self.timer = 8 * (self.speed as u32 + 1) * master_speed as u32;
+    }
+
+    fn tick(&mut self, master_speed: u8) {
+        if self.disabled || master_speed == 0 {
+            return;
+        }
+        self.timer = self.timer.saturating_sub(1);
+        if self.timer == 0 {
+            self.reset_timer(master_speed);
+            if self.increase && self.gain < 32 {
+                self.gain += 1;
+            } else if !self.increase && self.gain > 0 {
+                self.gain -= 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FdsAudio {
+    wave_table: [u8; 64],
+    wave_write: bool, // Table is writable and the output holds
+    master_volume: u8,
+    envelope_speed: u8,
+    volume: Envelope,
+    modulation: Envelope,
+    wave_frequency: u16,
+    wave_halt: bool,
+    envelope_halt: bool,
+    wave_accumulator: u32,
+    wave_position: usize,
+    mod_frequency: u16,
+    mod_halt: bool,
+    mod_accumulator: u32,
+    mod_table: [u8; 64],
+    mod_position: usize,
+    mod_counter: i32, // 7-bit signed
+    level: u8,
+}
+
+impl FdsAudio {
+    pub fn new() -> Self {
+        Self {
+            wave_table: [0; 64],
+            wave_write: false,
+            master_volume: 0,
+            envelope_speed: 0xE8,
+            volume: Envelope::default(),
+            modulation: Envelope::default(),
+            wave_frequency: 0,
+            wave_halt: true,
+            envelope_halt: false,
+            wave_accumulator: 0,
+            wave_position: 0,
+            mod_frequency: 0,
+            mod_halt: true,
+            mod_accumulator: 0,
+            mod_table: [0; 64],
+            mod_position: 0,
+            mod_counter: 0,
+            level: 0,
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> Option<u8> {
+        // Only the low 6 bits are driven, the rest is open bus
+        match addr {
+            0x4040..=0x407F => Some(self.wave_table[(addr - 0x4040) as usize] | 0x40),
+            0x4090 => Some(self.volume.gain | 0x40),
+            0x4092 => Some(self.modulation.gain | 0x40),
+            _ => None,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x4040..=0x407F if self.wave_write => {
+                self.wave_table[(addr - 0x4040) as usize] = value & 0x3F;
+            }
+            0x4080 => self.volume.write(value, self.envelope_speed),
+            0x4082 => self.wave_frequency = (self.wave_frequency & 0x0F00) | value as u16,
+            0x4083 => {
+                self.wave_frequency = (self.wave_frequency & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.wave_halt = value & 0x80 != 0;
+                self.envelope_halt = value & 0x40 != 0;
+                if self.wave_halt {
+                    self.wave_accumulator = 0;
+                    self.wave_position = 0;
+                }
+            }
+            0x4084 => self.modulation.write(value, self.envelope_speed),
+            0x4085 => {
+                // Sign extend the 7-bit counter
+                self.mod_counter = (((value & 0x7F) << 1) as i8 >> 1) as i32;
+            }
+            0x4086 => self.mod_frequency = (self.mod_frequency & 0x0F00) | value as u16,
+            0x4087 => {
+                self.mod_frequency = (self.mod_frequency & 0x00FF) | ((value as u16 & 0x0F) << 8);
+                self.mod_halt = value & 0x80 != 0;
+                if self.mod_halt {
+                    self.mod_accumulator = 0;
+                }
+            }
+            0x4088 if self.mod_halt => {
+                // Each write fills two steps, the table is only writable while halted
+                self.mod_table[self.mod_position] = value & 0x07;
+                self.mod_table[self.mod_position + 1] = value & 0x07;
+                self.mod_position = (self.mod_position + 2) & 0x3F;
+            }
+            0x4089 => {
+                self.master_volume = value & 0x03;
+                self.wave_write = value & 0x80 != 0;
+            }
+            0x408A => self.envelope_speed = value,
+            _ => {}
+        }
+    }
+
+    pub fn tick(&mut self) {
+        // Clocked once per CPU cycle
+        if !self.wave_halt && !self.envelope_halt {
+            self.volume.tick(self.envelope_speed);
+            self.modulation.tick(self.envelope_speed);
+        }
+
+        if !self.mod_halt && self.mod_frequency > 0 {
+            self.mod_accumulator += self.mod_frequency as u32;
+            if self.mod_accumulator > 0xFFFF {
+                self.mod_accumulator &= 0xFFFF;
+                self.step_modulator();
+            }
+        }
+
+        if !self.wave_halt && !self.wave_write {
+            self.wave_accumulator += self.modulated_pitch();
+            if self.wave_accumulator > 0xFFFF {
+                self.wave_accumulator &= 0xFFFF;
+                self.wave_position = (self.wave_position + 1) & 0x3F;
+                self.level = self.wave_table[self.wave_position];
+            }
+        }
+    }
+
+    fn step_modulator(&mut self) {
+        let step = self.mod_table[self.mod_position] as usize;
+        self.mod_position = (self.mod_position + 1) & 0x3F;
+        self.mod_counter = if step == 4 {
+            0
+        } else {
+            // Wrap within -64..=63
+            ((self.mod_counter + MOD_STEPS[step] + 64) & 0x7F) - 64
+        };
+    }
+
+    fn modulated_pitch(&self) -> u32 {
+        // Integer math from the hardware, see the nesdev wiki FDS audio page
+        let pitch = self.wave_frequency as i32;
+        if self.mod_halt {
+            return pitch as u32;
+        }
+        let mut temp = self.mod_counter * self.modulation.gain as i32;
+        let remainder = temp & 0x0F;
+        temp >>= 4;
+        if remainder > 0 && temp & 0x80 == 0 {
+            temp += if self.mod_counter < 0 { -1 } else { 2 };
+        }
+        if temp >= 192 {
+            temp -= 256;
+        } else if temp < -64 {
+            temp += 256;
+        }
+        temp *= pitch;
+        let remainder = temp & 0x3F;
+        temp >>= 6;
+        if remainder >= 32 {
+            temp += 1;
+        }
+        (pitch + temp).max(0) as u32
+    }
+
+    pub fn output(&self) -> f32 {
+        let gain = self.volume.gain.min(32) as f32;
+        self.level as f32 * gain / (63.0 * 32.0)
+            * MASTER_VOLUME[self.master_volume as usize]
+            * OUTPUT_LEVEL
+    }
+}
@@ -0,0 +1,72 @@
+use super::super::error::{CartridgeError, RomSection};
+
+pub const SIDE_SIZE: usize = 65500;
+const FDS_MAGIC: &[u8] = b"FDS\x1A";
+const DISK_INFO_MAGIC: &[u8] = b"\x01*NINTENDO-HVC*";
+const LEAD_IN: usize = 28300 / 8; // Gap before the first block, in bytes
+const BLOCK_GAP: usize = 976 / 8; // Gap after each block
+
+pub fn is_disk_image(data: &[u8]) -> bool {
+    // fwNES images have a 16 byte header, raw dumps start with the disk info block
+    data.starts_with(FDS_MAGIC) || data.starts_with(DISK_INFO_MAGIC)
+}
+
+pub fn parse_sides(data: &[u8]) -> Result<Vec<Vec<u8>>, CartridgeError> {
+    let (declared, body) = if data.starts_with(FDS_MAGIC) {
+        (
+            data.get(4).copied().unwrap_or(0) as usize,
+            data.get(16..).unwrap_or_default(),
+        )
+    } else {
+        (0, data)
+    };
+    // Some headers claim zero sides, count them from the size instead
+    let sides = if declared == 0 {
+        (body.len() / SIDE_SIZE).max(1)
+    } else {
+        declared
+    };
+
+    (0..sides)
+        .map(|side| {
+            let start = side * SIDE_SIZE;
+            body.get(start..start + SIDE_SIZE)
+                .map(add_gaps)
+                .ok_or(CartridgeError::Truncated {
+                    section: RomSection::DiskSide(side),
+                    expected: SIDE_SIZE,
+                    got: body.len().saturating_sub(start),
+                })
+        })
+        .collect()
+}
+
+fn add_gaps(side: &[u8]) -> Vec<u8> {
+    // .fds files strip the gaps and CRCs the drive sees, put them back so the
+    // BIOS can find block starts by waiting for the 0x80 gap terminator
+    let mut raw = vec![0u8; LEAD_IN];
+    let mut offset = 0;
+    let mut file_size = 0;
+    while let Some(&block_type) = side.get(offset) {
+        let length = match block_type {
+            1 => 56, // Disk info
+            2 => 2,  // File amount
+            3 => 16, // File header
+            4 => 1 + file_size,
+            _ => break, // The rest of the side is unused
+        };
+        let Some(block) = side.get(offset..offset + length) else {
+            break;
+        };
+        if block_type == 3 {
+            file_size = u16::from_le_bytes([block[13], block[14]]) as usize;
+        }
+        raw.push(0x80);
+        raw.extend_from_slice(block);
+        raw.extend_from_slice(&[0x00, 0x00]); // CRC, never checked
+        raw.resize(raw.len() + BLOCK_GAP, 0);
+        offset += length;
+    }
+    raw.resize(raw.len().max(LEAD_IN + SIDE_SIZE), 0);
+    raw
+}
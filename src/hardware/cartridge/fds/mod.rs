@@ -0,0 +1,361 @@
+mod audio;
+mod disk;
+
+use super::ScreenMirroring;
+use super::mapper::Mapper;
+use audio::FdsAudio;
+use std::cell::Cell;
+
+pub use disk::{is_disk_image, parse_sides};
+
+pub const BIOS_SIZE: usize = 8 * 1024;
+const BYTE_CYCLES: u32 = 150; // CPU cycles per byte at the drive's 96.4 kbit/s
+const REWIND_CYCLES: u32 = 50_000; // Head travel back to the start of the disk
+const INSERT_CYCLES: u32 = 1_789_773; // Keep the drive empty for a second when swapping sides
+
+// Famicom Disk System RAM adapter: 32KB of RAM, 8KB of CHR-RAM, the BIOS at
+// $E000 and a disk drive that streams one byte at a time
+#[derive(Debug, Clone)]
+pub struct Fds {
+    bios: Vec<u8>,
+    prg_ram: Vec<u8>,
+    chr_ram: Vec<u8>,
+    sides: Vec<Vec<u8>>,
+    side: Option<usize>, // None while the drive is empty
+    next_side: Option<usize>,
+    insert_delay: u32,
+
+    disk_io_enabled: bool,
+    sound_io_enabled: bool,
+
+    // Timer IRQ
+    timer_reload: u16,
+    timer_counter: u16,
+    timer_repeat: bool,
+    timer_enabled: bool,
+    timer_irq: Cell<bool>, // Cells because reading $4030 acknowledges
+
+    // $4025
+    motor_on: bool,
+    transfer_reset: bool,
+    read_mode: bool,
+    horizontal_mirroring: bool,
+    crc_control: bool,
+    transfer_start: bool,
+    disk_irq_enabled: bool,
+
+    // Drive
+    disk_irq: Cell<bool>,
+    transfer_complete: Cell<bool>,
+    read_data: u8,
+    write_data: u8,
+    position: usize,
+    delay: u32,
+    scanning: bool,
+    end_of_head: bool,
+    gap_ended: bool,
+
+    audio: FdsAudio,
+}
+
+impl Fds {
+    pub fn new(bios: Vec<u8>, sides: Vec<Vec<u8>>) -> Self {
+        Self {
+            bios,
+            prg_ram: vec![0u8; 32 * 1024],
+            chr_ram: vec![0u8; 8 * 1024],
+            sides,
+            side: Some(0),
+            next_side: None,
+            insert_delay: 0,
+            disk_io_enabled: false,
+            sound_io_enabled: false,
+            timer_reload: 0,
+            timer_counter: 0,
+            timer_repeat: false,
+            timer_enabled: false,
+            timer_irq: Cell::new(false),
+            motor_on: false,
+            transfer_reset: false,
+            read_mode: true,
+            horizontal_mirroring: false,
+            crc_control: false,
+            transfer_start: false,
+            disk_irq_enabled: false,
+            disk_irq: Cell::new(false),
+            transfer_complete: Cell::new(false),
+            read_data: 0,
+            write_data: 0,
+            position: 0,
+            delay: 0,
+            scanning: false,
+            end_of_head: true,
+            gap_ended: false,
+            audio: FdsAudio::new(),
+        }
+    }
+
+    fn status(&self) -> u8 {
+        // $4030
+        let mut value = 0;
+        if self.timer_irq.get() {
+            value |= 0x01;
+        }
+        if self.transfer_complete.get() {
+            value |= 0x02;
+        }
+        if self.end_of_head {
+            value |= 0x40;
+        }
+        value
+    }
+
+    fn drive_status(&self) -> u8 {
+        // $4032: no disk, not ready and write protected bits
+        match self.side {
+            None => 0x40 | 0x07,
+            Some(_) if !self.scanning => 0x40 | 0x02,
+            Some(_) => 0x40,
+        }
+    }
+
+    fn clock_timer(&mut self) {
+        if !self.timer_enabled || !self.disk_io_enabled {
+            return;
+        }
+        if self.timer_counter == 0 {
+            self.timer_irq.set(true);
+            self.timer_counter = self.timer_reload;
+            if !self.timer_repeat {
+                self.timer_enabled = false;
+            }
+        } else {
+            self.timer_counter -= 1;
+        }
+    }
+
+    fn clock_drive(&mut self) {
+        if self.insert_delay > 0 {
+            self.insert_delay -= 1;
+            if self.insert_delay == 0 {
+                self.side = self.next_side.take();
+            }
+        }
+
+        let Some(side) = self.side else {
+            self.end_of_head = true;
+            self.scanning = false;
+            return;
+        };
+        if !self.motor_on {
+            self.end_of_head = true;
+            self.scanning = false;
+            return;
+        }
+        if self.transfer_reset && !self.scanning {
+            return;
+        }
+        if self.end_of_head {
+            // Rewind, then start streaming from the lead-in gap
+            self.delay = REWIND_CYCLES;
+            self.end_of_head = false;
+            self.position = 0;
+            self.gap_ended = false;
+            return;
+        }
+        if self.delay > 0 {
+            self.delay -= 1;
+            return;
+        }
+
+        self.scanning = true;
+        let mut raise_irq = self.disk_irq_enabled;
+        if self.read_mode {
+            let data = self.sides[side].get(self.position).copied().unwrap_or(0);
+            if !self.transfer_start {
+                self.gap_ended = false;
+            } else if data != 0 && !self.gap_ended {
+                // The 0x80 gap terminator, the block starts with the next byte
+                self.gap_ended = true;
+                raise_irq = false;
+            }
+            if self.gap_ended {
+                self.transfer_complete.set(true);
+                self.read_data = data;
+                if raise_irq {
+                    self.disk_irq.set(true);
+                }
+            }
+        } else {
+            if !self.crc_control {
+                self.transfer_complete.set(true);
+                if raise_irq {
+                    self.disk_irq.set(true);
+                }
+            }
+            // CRC bytes are written as zero, reads never check them
+            let data = if self.transfer_start && !self.crc_control {
+                self.write_data
+            } else {
+                0
+            };
+            if let Some(byte) = self.sides[side].get_mut(self.position) {
+                *byte = data;
+            }
+            self.gap_ended = false;
+        }
+
+        self.position += 1;
+        if self.position >= self.sides[side].len() {
+            self.motor_on = false;
+        } else {
+            self.delay = BYTE_CYCLES;
+        }
+    }
+}
+
+impl Mapper for Fds {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        let open_bus = (addr >> 8) as u8;
+        match addr {
+            0x4030 if self.disk_io_enabled => {
+                // Reading acknowledges both IRQs
+                let value = self.status();
+                self.timer_irq.set(false);
+                self.disk_irq.set(false);
+                self.transfer_complete.set(false);
+                Some(value)
+            }
+            0x4031 if self.disk_io_enabled => {
+                self.transfer_complete.set(false);
+                self.disk_irq.set(false);
+                Some(self.read_data)
+            }
+            0x4032 if self.disk_io_enabled => Some(self.drive_status()),
+            0x4033 if self.disk_io_enabled => Some(0x80), // Battery is good
+            0x4040..=0x4097 if self.sound_io_enabled => {
+                Some(self.audio.read(addr).unwrap_or(open_bus))
+            }
+            0x6000..=0xDFFF => Some(self.prg_ram[(addr - 0x6000) as usize]),
+            0xE000..=0xFFFF => Some(self.bios[(addr - 0xE000) as usize]),
+            _ => Some(open_bus),
+        }
+    }
+
+    fn peek(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x4030 if self.disk_io_enabled => Some(self.status()),
+            0x4031 if self.disk_io_enabled => Some(self.read_data),
+            _ => self.cpu_read(addr),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x4020 => self.timer_reload = (self.timer_reload & 0xFF00) | value as u16,
+            0x4021 => self.timer_reload = (self.timer_reload & 0x00FF) | (value as u16) << 8,
+            0x4022 => {
+                self.timer_repeat = value & 0x01 != 0;
+                self.timer_enabled = value & 0x02 != 0 && self.disk_io_enabled;
+                if self.timer_enabled {
+                    self.timer_counter = self.timer_reload;
+                } else {
+                    self.timer_irq.set(false);
+                }
+            }
+            0x4023 => {
+                self.disk_io_enabled = value & 0x01 != 0;
+                self.sound_io_enabled = value & 0x02 != 0;
+                if !self.disk_io_enabled {
+                    self.timer_enabled = false;
+                    self.timer_irq.set(false);
+                    self.disk_irq.set(false);
+                }
+            }
+            0x4024 if self.disk_io_enabled => {
+                self.write_data = value;
+                self.transfer_complete.set(false);
+                self.disk_irq.set(false);
+            }
+            0x4025 if self.disk_io_enabled => {
+                self.motor_on = value & 0x01 != 0;
+                self.transfer_reset = value & 0x02 != 0;
+                self.read_mode = value & 0x04 != 0;
+                self.horizontal_mirroring = value & 0x08 != 0;
+                self.crc_control = value & 0x10 != 0;
+                self.transfer_start = value & 0x40 != 0;
+                self.disk_irq_enabled = value & 0x80 != 0;
+                self.disk_irq.set(false);
+            }
+            0x4040..=0x408A if self.sound_io_enabled => self.audio.write(addr, value),
+            0x6000..=0xDFFF => self.prg_ram[(addr - 0x6000) as usize] = value,
+            _ => return false,
+        }
+        true
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr_ram.get(addr as usize).copied()
+    }
+
+    fn ppu_write(&mut self, addr: u16, value: u8) -> bool {
+        match self.chr_ram.get_mut(addr as usize) {
+            Some(byte) => {
+                *byte = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cpu_tick(&mut self) {
+        self.clock_timer();
+        self.clock_drive();
+        self.audio.tick();
+    }
+
+    fn audio_output(&self) -> f32 {
+        self.audio.output()
+    }
+
+    fn irq(&self) -> bool {
+        self.timer_irq.get() || self.disk_irq.get()
+    }
+
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        Some(if self.horizontal_mirroring {
+            ScreenMirroring::Horizontal
+        } else {
+            ScreenMirroring::Vertical
+        })
+    }
+
+    fn disk_sides(&self) -> usize {
+        self.sides.len()
+    }
+
+    fn insert_disk(&mut self, side: Option<usize>) {
+        // Eject first, the BIOS only notices a new side after seeing an empty drive
+        self.side = None;
+        self.next_side = side.filter(|&side| side < self.sides.len());
+        self.insert_delay = if self.next_side.is_some() {
+            INSERT_CYCLES
+        } else {
+            0
+        };
+    }
+
+    fn reset(&mut self) {
+        let bios = std::mem::take(&mut self.bios);
+        let sides = std::mem::take(&mut self.sides);
+        let side = self.side.or(self.next_side);
+        let prg_ram = std::mem::take(&mut self.prg_ram);
+        *self = Fds::new(bios, sides);
+        self.side = side;
+        self.prg_ram = prg_ram;
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+}
@@ -1,12 +1,68 @@
+use super::ScreenMirroring;
+use super::rom_info::RomInfo;
 use std::fmt::Debug;
 
 pub trait Mapper: Debug {
     fn cpu_read(&self, addr: u16) -> Option<u8>;
     fn cpu_write(&mut self, addr: u16, value: u8) -> bool;
 
+    // Same as cpu_read without side effects like acknowledging IRQs, for the debugger
+    fn peek(&self, addr: u16) -> Option<u8> {
+        self.cpu_read(addr)
+    }
+
     fn ppu_read(&self, addr: u16) -> Option<u8>;
     fn ppu_write(&mut self, addr: u16, value: u8) -> bool;
 
+    // Called for every address the PPU puts on its bus, boards like MMC3 watch A12
+    fn observe_ppu_address(&mut self, _addr: u16, _ppu_dot: u64) {}
+
+    // Boards like MMC5 can replace CIRAM for a nametable access, None falls back to CIRAM
+    fn nametable_read(&self, _addr: u16) -> Option<u8> {
+        None
+    }
+
+    // Returns true when the board consumed the write instead of CIRAM
+    fn nametable_write(&mut self, _addr: u16, _value: u8) -> bool {
+        false
+    }
+
+    // Level of the cartridge IRQ output
+    fn irq(&self) -> bool {
+        false
+    }
+
+    // Called once per CPU cycle, for boards with their own timers or sound
+    fn cpu_tick(&mut self) {}
+
+    // Expansion audio mixed on top of the APU, in the same 0.0..1.0 scale
+    fn audio_output(&self) -> f32 {
+        0.0
+    }
+
+    // Number of disk sides, zero for cartridges
+    fn disk_sides(&self) -> usize {
+        0
+    }
+
+    // Ejects the disk and inserts the given side, None leaves the drive empty
+    fn insert_disk(&mut self, _side: Option<usize>) {}
+
+    // Boards with mapper-controlled mirroring override the header setting
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        None
+    }
+
+    // Work RAM at $6000, saved to disk when the header has the battery flag
+    fn prg_ram(&self) -> Option<&[u8]> {
+        None
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        None
+    }
+
+    // Resets registers, PRG-RAM is kept like on a real console
     fn reset(&mut self);
 
     fn box_clone(&self) -> Box<dyn Mapper>;
@@ -18,25 +74,110 @@ impl Clone for Box<dyn Mapper> {
     }
 }
 
+pub fn open_bus(addr: u16) -> u8 {
+    // Nothing drives the bus, reads see its last value: usually the high byte of the address
+    (addr >> 8) as u8
+}
+
+pub fn bus_conflict(value: u8, rom_byte: u8) -> u8 {
+    // On boards without a write enable the ROM drives the bus too, so the register gets both ANDed
+    value & rom_byte
+}
+
+// Pattern memory of a board, CHR-ROM or CHR-RAM, indexed after banking
+#[derive(Debug, Clone)]
+pub struct Chr {
+    data: Vec<u8>,
+    is_ram: bool,
+}
+
+impl Chr {
+    pub fn new(info: &RomInfo, data: Vec<u8>) -> Self {
+        Self {
+            data,
+            is_ram: info.chr_is_ram(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn read(&self, index: usize) -> Option<u8> {
+        self.data.get(index).copied()
+    }
+
+    pub fn write(&mut self, index: usize, value: u8) -> bool {
+        // Writes to CHR-ROM are ignored
+        match self.data.get_mut(index) {
+            Some(byte) if self.is_ram => {
+                *byte = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        if self.is_ram {
+            self.data.fill(0);
+        }
+    }
+}
+
+// Work RAM at $6000-$7FFF, sized from the header
+#[derive(Debug, Clone)]
+pub struct PrgRam {
+    data: Vec<u8>,
+}
+
+impl PrgRam {
+    pub fn new(info: &RomInfo) -> Self {
+        Self {
+            data: vec![0; info.prg_ram_total()],
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        // Boards with less than 8KB leave the rest of the window open bus
+        self.data
+            .get((addr - 0x6000) as usize)
+            .copied()
+            .unwrap_or(open_bus(addr))
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) -> bool {
+        match self.data.get_mut((addr - 0x6000) as usize) {
+            Some(byte) => {
+                *byte = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        (!self.data.is_empty()).then_some(self.data.as_slice())
+    }
+
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        (!self.data.is_empty()).then_some(self.data.as_mut_slice())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Mapper0 {
     prg_rom: Vec<u8>,
-    prg_ram: Vec<u8>,
-    pub chr: Vec<u8>,
-    chr_is_ram: bool,
-    nametable: [u8; 4096],
+    prg_ram: PrgRam,
+    chr: Chr,
 }
 
 impl Mapper0 {
-    pub fn new(prg_rom: Vec<u8>, chr: Vec<u8>, chr_is_ram: bool) -> Self {
-        let prg_ram = vec![0u8; 8 * 1024]; // 8KB default
-
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
         Self {
             prg_rom,
-            prg_ram,
-            chr,
-            chr_is_ram,
-            nametable: [0; 4096], // Initialize nametable to zero
+            prg_ram: PrgRam::new(info), // iNES 1 images always get 8KB, NES 2.0 gives the real size
+            chr: Chr::new(info, chr),
         }
     }
 }
@@ -45,16 +186,7 @@ impl Mapper for Mapper0 {
     fn cpu_read(&self, addr: u16) -> Option<u8> {
         match addr {
             // PRG RAM (Optional, typically 0x6000-0x7FFF)
-            0x6000..=0x7FFF => {
-                // Mask to get the offset within the 8KB block.
-                let index = (addr - 0x6000) as usize;
-                if index < self.prg_ram.len() {
-                    Some(self.prg_ram[index])
-                } else {
-                    eprintln!("CPU Read: PRG RAM address 0x{:04X} out of bounds.", addr);
-                    None
-                }
-            }
+            0x6000..=0x7FFF => Some(self.prg_ram.read(addr)),
             // PRG ROM (0x8000-0xFFFF)
             0x8000..=0xFFFF => {
                 // Mapper0 (NROM) can have 16KB or 32KB PRG ROM.
@@ -76,74 +208,34 @@ impl Mapper for Mapper0 {
                     None
                 }
             }
-            _ => {
-                // Addresses outside cartridge memory space (handled by Bus)
-                None
-            }
+            _ => Some(open_bus(addr)),
         }
     }
 
     fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
         match addr {
             // PRG RAM
-            0x6000..=0x7FFF => {
-                let index = (addr - 0x6000) as usize;
-                if index < self.prg_ram.len() {
-                    self.prg_ram[index] = value;
-                    true // Write successful
-                } else {
-                    eprintln!("CPU Write: PRG RAM address 0x{:04X} out of bounds.", addr);
-                    false
-                }
-            }
+            0x6000..=0x7FFF => self.prg_ram.write(addr, value),
             // PRG ROM (Writes to ROM are ignored)
-            0x8000..=0xFFFF => {
-                eprintln!("CPU Write: Attempted to write to PRG ROM at 0x{:04X}", addr);
-                false
-            }
-            _ => false, // Address outside cartridge memory space
+            _ => false, // PRG ROM writes are ignored
         }
     }
 
     fn ppu_read(&self, addr: u16) -> Option<u8> {
-        let index = (addr & 0x3FFF) as usize;
-        if index < 0x2000 {
-            let data = self.chr[index];
-            Some(data)
-        } else if index < 0x3000 {
-            // CHR-ROM is read-only, so we can return the data directly.
-            let data = self.nametable[index - 0x2000];
-            Some(data)
-        } else if index < 0x3F00 {
-            // This is typically not used in Mapper0, but we can return a default value.
-            Some(0) // Default palette value
-        } else {
-            eprintln!("PPU Read: CHR address 0x{:04X} out of bounds.", addr);
-            None
-        }
+        // Nothing is loaded yet when the cartridge is empty
+        Some(self.chr.read((addr & 0x1FFF) as usize).unwrap_or(0))
     }
 
     fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
-        // PPU addresses 0x0000-0x1FFF map directly to CHR data on Mapper0.
-        // Only allow writes if chr_is_ram is true.
-        let index = addr as usize;
-        if index <= 0x1FFF {
-            if self.chr_is_ram {
-                self.chr[index] = data;
-                true // Write successful
-            } else {
-                eprintln!("PPU Write: Attempted to write to CHR-ROM at 0x{:04X}", addr);
-                false // Write ignored
-            }
-        } else if index < 0x3000 {
-            self.nametable[index - 0x2000] = data;
-            true // Write successful
-        } else if index < 0x3F00 {
-            true
-        } else {
-            eprintln!("PPU Write: CHR RAM address 0x{:04X} out of bounds.", addr);
-            false // Write failed
-        }
+        self.chr.write((addr & 0x1FFF) as usize, data)
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        self.prg_ram.as_slice()
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        self.prg_ram.as_mut_slice()
     }
 
     fn box_clone(&self) -> Box<dyn Mapper> {
@@ -151,10 +243,19 @@ impl Mapper for Mapper0 {
     }
 
     fn reset(&mut self) {
-        self.prg_ram.fill(0);
-        self.nametable.fill(0);
-        if self.chr_is_ram {
-            self.chr.fill(0);
-        }
+        self.chr.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nrom_reads_open_bus_below_prg_ram() {
+        let mapper = Mapper0::new(&RomInfo::default(), vec![0xEA; 0x4000], vec![0; 0x2000]);
+        assert_eq!(mapper.cpu_read(0x4020), Some(0x40));
+        assert_eq!(mapper.cpu_read(0x5000), Some(0x50));
+        assert_eq!(mapper.cpu_read(0x8000), Some(0xEA));
     }
 }
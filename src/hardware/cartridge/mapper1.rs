@@ -0,0 +1,220 @@
+use super::ScreenMirroring;
+use super::mapper::{Chr, Mapper, PrgRam, open_bus};
+use super::rom_info::RomInfo;
+
+// MMC1 (SxROM boards)
+#[derive(Debug, Clone)]
+pub struct Mapper1 {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+
+    shift_register: u8, // Bit 4 marks a full register once it reaches bit 0
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8,
+
+    cycle: u64, // CPU cycles seen through cpu_tick
+    last_write_cycle: Option<u64>,
+}
+
+impl Mapper1 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            prg_ram: PrgRam::new(info),
+            chr: Chr::new(info, chr),
+            shift_register: 0x10,
+            control: 0x0C, // Power on with the last PRG bank fixed at $C000
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0,
+            cycle: 0,
+            last_write_cycle: None,
+        }
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_bank & 0x10 == 0
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank_count = (self.prg_rom.len() / 0x4000).max(1);
+        // 512KB boards (SUROM) use CHR bank 0 bit 4 to select a 256KB half
+        let outer = if self.prg_rom.len() > 0x40000 {
+            (self.chr_bank_0 & 0x10) as usize
+        } else {
+            0
+        };
+        let bank = (self.prg_bank & 0x0F) as usize;
+        let upper = addr >= 0xC000;
+
+        let selected = match (self.control >> 2) & 0x03 {
+            // 32KB mode ignores the low bit of the bank number
+            0 | 1 => (bank & 0x0E) | upper as usize,
+            // First bank fixed at $8000, switch $C000
+            2 => {
+                if upper {
+                    bank
+                } else {
+                    0
+                }
+            }
+            // Last bank fixed at $C000, switch $8000
+            _ => {
+                if upper {
+                    0x0F
+                } else {
+                    bank
+                }
+            }
+        };
+        ((outer | selected) % bank_count) * 0x4000 + (addr & 0x3FFF) as usize
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank_count = (self.chr.len() / 0x1000).max(1);
+        let bank = if self.control & 0x10 == 0 {
+            // 8KB mode ignores the low bit of CHR bank 0
+            (self.chr_bank_0 & 0x1E) as usize | (addr >= 0x1000) as usize
+        } else if addr < 0x1000 {
+            self.chr_bank_0 as usize
+        } else {
+            self.chr_bank_1 as usize
+        };
+        (bank % bank_count) * 0x1000 + (addr & 0x0FFF) as usize
+    }
+
+    fn write_register(&mut self, addr: u16, value: u8) {
+        // Register is selected by address bits 13 and 14 of the fifth write
+        match addr {
+            0x8000..=0x9FFF => self.control = value,
+            0xA000..=0xBFFF => self.chr_bank_0 = value,
+            0xC000..=0xDFFF => self.chr_bank_1 = value,
+            _ => self.prg_bank = value,
+        }
+    }
+}
+
+impl Mapper for Mapper1 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled() => Some(self.prg_ram.read(addr)),
+            0x8000..=0xFFFF => self.prg_rom.get(self.prg_rom_index(addr)).copied(),
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x6000..=0x7FFF => self.prg_ram_enabled() && self.prg_ram.write(addr, value),
+            0x8000..=0xFFFF => {
+                // Writes on back-to-back cycles are ignored, so the second write
+                // of a read-modify-write instruction never reaches the register
+                let consecutive = self.last_write_cycle == Some(self.cycle.wrapping_sub(1));
+                self.last_write_cycle = Some(self.cycle);
+                if consecutive {
+                    return true;
+                }
+                if value & 0x80 != 0 {
+                    // Reset the shift register and fix the last bank at $C000
+                    self.shift_register = 0x10;
+                    self.control |= 0x0C;
+                    return true;
+                }
+                let full = self.shift_register & 0x01 != 0;
+                self.shift_register = (self.shift_register >> 1) | ((value & 0x01) << 4);
+                if full {
+                    let register = self.shift_register;
+                    self.write_register(addr, register);
+                    self.shift_register = 0x10;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write(self.chr_index(addr), data)
+    }
+
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        Some(match self.control & 0x03 {
+            0 => ScreenMirroring::SingleLower,
+            1 => ScreenMirroring::SingleUpper,
+            2 => ScreenMirroring::Vertical,
+            _ => ScreenMirroring::Horizontal,
+        })
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        self.prg_ram.as_slice()
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        self.prg_ram.as_mut_slice()
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn cpu_tick(&mut self) {
+        self.cycle += 1;
+    }
+
+    fn reset(&mut self) {
+        self.shift_register = 0x10;
+        self.control = 0x0C;
+        self.chr_bank_0 = 0;
+        self.chr_bank_1 = 0;
+        self.prg_bank = 0;
+        self.chr.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mmc1() -> Mapper1 {
+        // 256KB of PRG-ROM, each 16KB bank filled with its number
+        let prg_rom = (0..16).flat_map(|bank| vec![bank as u8; 0x4000]).collect();
+        Mapper1::new(&RomInfo::default(), prg_rom, vec![0; 0x2000])
+    }
+
+    fn serial_write(mapper: &mut Mapper1, addr: u16, value: u8) {
+        // Five writes a few cycles apart, like a STA/LSR loop
+        for bit in 0..5 {
+            mapper.cpu_tick();
+            mapper.cpu_tick();
+            mapper.cpu_write(addr, (value >> bit) & 0x01);
+        }
+    }
+
+    #[test]
+    fn serial_writes_select_prg_bank() {
+        let mut mapper = mmc1();
+        serial_write(&mut mapper, 0xE000, 0x03);
+        assert_eq!(mapper.cpu_read(0x8000), Some(0x03));
+        assert_eq!(mapper.cpu_read(0xC000), Some(0x0F));
+    }
+
+    #[test]
+    fn consecutive_write_is_ignored() {
+        let mut mapper = mmc1();
+        // INC on ROM writes the old value and then the new one on the next cycle
+        mapper.cpu_tick();
+        mapper.cpu_write(0x8000, 0x80);
+        mapper.cpu_tick();
+        mapper.cpu_write(0x8000, 0x01);
+        serial_write(&mut mapper, 0xE000, 0x05);
+        assert_eq!(mapper.cpu_read(0x8000), Some(0x05));
+    }
+}
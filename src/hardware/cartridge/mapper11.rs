@@ -0,0 +1,79 @@
+use super::mapper::{Chr, Mapper, bus_conflict, open_bus};
+use super::rom_info::RomInfo;
+
+// Color Dreams: 32KB PRG bank in bits 0-1, 8KB CHR bank in bits 4-7
+#[derive(Debug, Clone)]
+pub struct Mapper11 {
+    prg_rom: Vec<u8>,
+    chr: Chr,
+    bus_conflicts: bool,
+
+    prg_bank: u8,
+    chr_bank: u8,
+}
+
+impl Mapper11 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            chr: Chr::new(info, chr),
+            bus_conflicts: info.bus_conflicts(),
+            prg_bank: 0,
+            chr_bank: 0,
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank_count = (self.chr.len() / 0x2000).max(1);
+        (self.chr_bank as usize % bank_count) * 0x2000 + (addr & 0x1FFF) as usize
+    }
+}
+
+impl Mapper for Mapper11 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom.len() / 0x8000).max(1);
+                let bank = self.prg_bank as usize % bank_count;
+                self.prg_rom
+                    .get(bank * 0x8000 + (addr & 0x7FFF) as usize)
+                    .copied()
+            }
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x8000..=0xFFFF => {
+                let value = if self.bus_conflicts {
+                    bus_conflict(value, self.cpu_read(addr).unwrap_or(0xFF))
+                } else {
+                    value
+                };
+                self.prg_bank = value & 0x03;
+                self.chr_bank = value >> 4;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write(self.chr_index(addr), data)
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.prg_bank = 0;
+        self.chr_bank = 0;
+        self.chr.reset();
+    }
+}
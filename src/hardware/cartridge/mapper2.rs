@@ -0,0 +1,73 @@
+use super::mapper::{Chr, Mapper, bus_conflict, open_bus};
+use super::rom_info::RomInfo;
+
+// UxROM: switchable 16KB bank at $8000, last bank fixed at $C000
+#[derive(Debug, Clone)]
+pub struct Mapper2 {
+    prg_rom: Vec<u8>,
+    chr: Chr,
+    bus_conflicts: bool,
+
+    prg_bank: u8,
+}
+
+impl Mapper2 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            chr: Chr::new(info, chr),
+            bus_conflicts: info.bus_conflicts(),
+            prg_bank: 0,
+        }
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank_count = (self.prg_rom.len() / 0x4000).max(1);
+        let bank = if addr < 0xC000 {
+            self.prg_bank as usize % bank_count
+        } else {
+            bank_count - 1
+        };
+        bank * 0x4000 + (addr & 0x3FFF) as usize
+    }
+}
+
+impl Mapper for Mapper2 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x8000..=0xFFFF => self.prg_rom.get(self.prg_rom_index(addr)).copied(),
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.prg_bank = if self.bus_conflicts {
+                    bus_conflict(value, self.cpu_read(addr).unwrap_or(0xFF))
+                } else {
+                    value
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read((addr & 0x1FFF) as usize)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write((addr & 0x1FFF) as usize, data)
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.prg_bank = 0;
+        self.chr.reset();
+    }
+}
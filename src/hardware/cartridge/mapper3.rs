@@ -0,0 +1,72 @@
+use super::mapper::{Chr, Mapper, bus_conflict, open_bus};
+use super::rom_info::RomInfo;
+
+// CNROM: fixed PRG like NROM, switchable 8KB CHR bank
+#[derive(Debug, Clone)]
+pub struct Mapper3 {
+    prg_rom: Vec<u8>,
+    chr: Chr,
+    bus_conflicts: bool,
+
+    chr_bank: u8,
+}
+
+impl Mapper3 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            chr: Chr::new(info, chr),
+            bus_conflicts: info.bus_conflicts(),
+            chr_bank: 0,
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank_count = (self.chr.len() / 0x2000).max(1);
+        (self.chr_bank as usize % bank_count) * 0x2000 + (addr & 0x1FFF) as usize
+    }
+}
+
+impl Mapper for Mapper3 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x8000..=0xFFFF => {
+                // 16KB images are mirrored into $C000-$FFFF
+                let index = (addr - 0x8000) as usize % self.prg_rom.len().max(1);
+                self.prg_rom.get(index).copied()
+            }
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x8000..=0xFFFF => {
+                self.chr_bank = if self.bus_conflicts {
+                    bus_conflict(value, self.cpu_read(addr).unwrap_or(0xFF))
+                } else {
+                    value
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write(self.chr_index(addr), data)
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.chr_bank = 0;
+        self.chr.reset();
+    }
+}
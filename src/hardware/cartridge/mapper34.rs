@@ -0,0 +1,113 @@
+use super::mapper::{Chr, Mapper, PrgRam, bus_conflict, open_bus};
+use super::rom_info::RomInfo;
+
+// BNROM and NINA-001 share mapper 34, NINA-001 is the one with CHR-ROM banking
+#[derive(Debug, Clone)]
+pub struct Mapper34 {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    bus_conflicts: bool,
+    nina: bool,
+
+    prg_bank: u8,
+    chr_banks: [u8; 2], // 4KB banks, NINA-001 only
+}
+
+impl Mapper34 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        // NES 2.0 submappers name the board, otherwise only NINA-001 has more than 8KB of CHR
+        let nina = match info.submapper {
+            1 => true,
+            2 => false,
+            _ => !info.chr_is_ram() && chr.len() > 0x2000,
+        };
+        Self {
+            prg_rom,
+            prg_ram: PrgRam::new(info),
+            chr: Chr::new(info, chr),
+            bus_conflicts: info.bus_conflicts(),
+            nina,
+            prg_bank: 0,
+            chr_banks: [0, 1],
+        }
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        if !self.nina {
+            return (addr & 0x1FFF) as usize;
+        }
+        let bank_count = (self.chr.len() / 0x1000).max(1);
+        let bank = self.chr_banks[(addr >> 12) as usize & 0x01] as usize;
+        (bank % bank_count) * 0x1000 + (addr & 0x0FFF) as usize
+    }
+}
+
+impl Mapper for Mapper34 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x6000..=0x7FFF => Some(self.prg_ram.read(addr)),
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom.len() / 0x8000).max(1);
+                let bank = self.prg_bank as usize % bank_count;
+                self.prg_rom
+                    .get(bank * 0x8000 + (addr & 0x7FFF) as usize)
+                    .copied()
+            }
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x6000..=0x7FFF => {
+                // NINA-001 registers sit on top of PRG-RAM and the write goes to both
+                self.prg_ram.write(addr, value);
+                if self.nina {
+                    match addr {
+                        0x7FFD => self.prg_bank = value & 0x01,
+                        0x7FFE => self.chr_banks[0] = value & 0x0F,
+                        0x7FFF => self.chr_banks[1] = value & 0x0F,
+                        _ => {}
+                    }
+                }
+                true
+            }
+            0x8000..=0xFFFF if !self.nina => {
+                self.prg_bank = if self.bus_conflicts {
+                    bus_conflict(value, self.cpu_read(addr).unwrap_or(0xFF))
+                } else {
+                    value
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write(self.chr_index(addr), data)
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        self.prg_ram.as_slice()
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        self.prg_ram.as_mut_slice()
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.prg_bank = 0;
+        self.chr_banks = [0, 1];
+        self.chr.reset();
+    }
+}
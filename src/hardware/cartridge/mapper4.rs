@@ -0,0 +1,231 @@
+use super::ScreenMirroring;
+use super::mapper::{Chr, Mapper, PrgRam, open_bus};
+use super::rom_info::RomInfo;
+
+// PPU dots A12 has to stay low before a rising edge clocks the IRQ counter,
+// the real filter counts roughly three falling edges of M2
+const A12_LOW_DOTS: u64 = 10;
+
+// MMC3 (TxROM boards)
+#[derive(Debug, Clone)]
+pub struct Mapper4 {
+    prg_rom: Vec<u8>,
+    prg_ram: PrgRam,
+    chr: Chr,
+    four_screen: bool,
+
+    bank_select: u8,
+    registers: [u8; 8], // R0-R5 CHR banks, R6-R7 PRG banks
+    horizontal_mirroring: bool,
+    prg_ram_enabled: bool,
+    prg_ram_write_protect: bool,
+
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+    a12_high: bool,
+    a12_low_since: u64, // PPU dot at which A12 last went low
+}
+
+impl Mapper4 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            prg_ram: PrgRam::new(info),
+            chr: Chr::new(info, chr),
+            four_screen: info.mirroring == ScreenMirroring::FourScreen,
+            bank_select: 0,
+            registers: [0, 2, 4, 5, 6, 7, 0, 1],
+            horizontal_mirroring: false,
+            prg_ram_enabled: true,
+            prg_ram_write_protect: false,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+            a12_high: false,
+            a12_low_since: 0,
+        }
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank_count = (self.prg_rom.len() / 0x2000).max(1);
+        let second_last = bank_count.saturating_sub(2);
+        let swapped = self.bank_select & 0x40 != 0;
+        let bank = match (addr >> 13) & 0x03 {
+            // $8000 and $C000 trade places in PRG mode 1
+            0 => {
+                if swapped {
+                    second_last
+                } else {
+                    self.registers[6] as usize
+                }
+            }
+            1 => self.registers[7] as usize,
+            2 => {
+                if swapped {
+                    self.registers[6] as usize
+                } else {
+                    second_last
+                }
+            }
+            _ => bank_count - 1,
+        };
+        // Only 6 bits of the bank number are wired
+        ((bank & 0x3F) % bank_count) * 0x2000 + (addr & 0x1FFF) as usize
+    }
+
+    fn chr_index(&self, addr: u16) -> usize {
+        let bank_count = (self.chr.len() / 0x0400).max(1);
+        // CHR inversion swaps the 2KB and 1KB halves
+        let addr = if self.bank_select & 0x80 != 0 {
+            addr ^ 0x1000
+        } else {
+            addr
+        };
+        let bank = match addr >> 10 {
+            0 => self.registers[0] & 0xFE,
+            1 => self.registers[0] | 0x01,
+            2 => self.registers[1] & 0xFE,
+            3 => self.registers[1] | 0x01,
+            slot => self.registers[(slot - 2) as usize],
+        };
+        (bank as usize % bank_count) * 0x0400 + (addr & 0x03FF) as usize
+    }
+
+    fn clock_irq_counter(&mut self) {
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+}
+
+impl Mapper for Mapper4 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x6000..=0x7FFF if self.prg_ram_enabled => Some(self.prg_ram.read(addr)),
+            0x8000..=0xFFFF => self.prg_rom.get(self.prg_rom_index(addr)).copied(),
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match (addr, addr & 0x01 == 0) {
+            (0x6000..=0x7FFF, _) => {
+                self.prg_ram_enabled
+                    && !self.prg_ram_write_protect
+                    && self.prg_ram.write(addr, value)
+            }
+            (0x8000..=0x9FFF, true) => {
+                self.bank_select = value;
+                true
+            }
+            (0x8000..=0x9FFF, false) => {
+                self.registers[(self.bank_select & 0x07) as usize] = value;
+                true
+            }
+            (0xA000..=0xBFFF, true) => {
+                self.horizontal_mirroring = value & 0x01 != 0;
+                true
+            }
+            (0xA000..=0xBFFF, false) => {
+                self.prg_ram_enabled = value & 0x80 != 0;
+                self.prg_ram_write_protect = value & 0x40 != 0;
+                true
+            }
+            (0xC000..=0xDFFF, true) => {
+                self.irq_latch = value;
+                true
+            }
+            (0xC000..=0xDFFF, false) => {
+                // Reload on the next A12 rising edge
+                self.irq_counter = 0;
+                self.irq_reload = true;
+                true
+            }
+            (0xE000..=0xFFFF, true) => {
+                // Disabling also acknowledges a pending IRQ
+                self.irq_enabled = false;
+                self.irq_pending = false;
+                true
+            }
+            (0xE000..=0xFFFF, false) => {
+                self.irq_enabled = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read(self.chr_index(addr))
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write(self.chr_index(addr), data)
+    }
+
+    fn observe_ppu_address(&mut self, addr: u16, ppu_dot: u64) {
+        // The scanline counter is clocked by filtered rising edges of PPU A12
+        let a12_high = addr & 0x1000 != 0;
+        if a12_high && !self.a12_high {
+            if ppu_dot.wrapping_sub(self.a12_low_since) >= A12_LOW_DOTS {
+                self.clock_irq_counter();
+            }
+        } else if !a12_high && self.a12_high {
+            self.a12_low_since = ppu_dot;
+        }
+        self.a12_high = a12_high;
+    }
+
+    fn irq(&self) -> bool {
+        self.irq_pending
+    }
+
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        Some(if self.four_screen {
+            ScreenMirroring::FourScreen
+        } else if self.horizontal_mirroring {
+            ScreenMirroring::Horizontal
+        } else {
+            ScreenMirroring::Vertical
+        })
+    }
+
+    fn prg_ram(&self) -> Option<&[u8]> {
+        self.prg_ram.as_slice()
+    }
+
+    fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
+        self.prg_ram.as_mut_slice()
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.bank_select = 0;
+        self.registers = [0, 2, 4, 5, 6, 7, 0, 1];
+        self.horizontal_mirroring = false;
+        self.prg_ram_enabled = true;
+        self.prg_ram_write_protect = false;
+        self.irq_latch = 0;
+        self.irq_counter = 0;
+        self.irq_reload = false;
+        self.irq_enabled = false;
+        self.irq_pending = false;
+        self.a12_high = false;
+        self.a12_low_since = 0;
+        self.chr.reset();
+    }
+}
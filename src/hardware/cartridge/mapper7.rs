@@ -0,0 +1,84 @@
+use super::ScreenMirroring;
+use super::mapper::{Chr, Mapper, bus_conflict, open_bus};
+use super::rom_info::RomInfo;
+
+// AxROM: switchable 32KB PRG bank and single-screen mirroring select
+#[derive(Debug, Clone)]
+pub struct Mapper7 {
+    prg_rom: Vec<u8>,
+    chr: Chr,
+    bus_conflicts: bool,
+
+    prg_bank: u8,
+    upper_screen: bool,
+}
+
+impl Mapper7 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            chr: Chr::new(info, chr),
+            bus_conflicts: info.bus_conflicts(),
+            prg_bank: 0,
+            upper_screen: false,
+        }
+    }
+}
+
+impl Mapper for Mapper7 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x8000..=0xFFFF => {
+                let bank_count = (self.prg_rom.len() / 0x8000).max(1);
+                let bank = (self.prg_bank & 0x0F) as usize % bank_count;
+                self.prg_rom
+                    .get(bank * 0x8000 + (addr & 0x7FFF) as usize)
+                    .copied()
+            }
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x8000..=0xFFFF => {
+                // AMROM and AOROM have bus conflicts, ANROM does not
+                let value = if self.bus_conflicts {
+                    bus_conflict(value, self.cpu_read(addr).unwrap_or(0xFF))
+                } else {
+                    value
+                };
+                self.prg_bank = value & 0x0F;
+                self.upper_screen = value & 0x10 != 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read((addr & 0x1FFF) as usize)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write((addr & 0x1FFF) as usize, data)
+    }
+
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        Some(if self.upper_screen {
+            ScreenMirroring::SingleUpper
+        } else {
+            ScreenMirroring::SingleLower
+        })
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.prg_bank = 0;
+        self.upper_screen = false;
+        self.chr.reset();
+    }
+}
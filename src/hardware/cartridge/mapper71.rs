@@ -0,0 +1,84 @@
+use super::ScreenMirroring;
+use super::mapper::{Chr, Mapper, open_bus};
+use super::rom_info::RomInfo;
+
+// Camerica/Codemasters: UxROM-like, bank select lives at $C000-$FFFF
+#[derive(Debug, Clone)]
+pub struct Mapper71 {
+    prg_rom: Vec<u8>,
+    chr: Chr,
+    mirroring: ScreenMirroring,
+    one_screen_select: bool, // Fire Hawk's BF9097 board, NES 2.0 submapper 1
+
+    prg_bank: u8,
+}
+
+impl Mapper71 {
+    pub fn new(info: &RomInfo, prg_rom: Vec<u8>, chr: Vec<u8>) -> Self {
+        Self {
+            prg_rom,
+            chr: Chr::new(info, chr),
+            mirroring: info.mirroring,
+            one_screen_select: info.submapper == 1,
+            prg_bank: 0,
+        }
+    }
+
+    fn prg_rom_index(&self, addr: u16) -> usize {
+        let bank_count = (self.prg_rom.len() / 0x4000).max(1);
+        let bank = if addr < 0xC000 {
+            self.prg_bank as usize % bank_count
+        } else {
+            bank_count - 1
+        };
+        bank * 0x4000 + (addr & 0x3FFF) as usize
+    }
+}
+
+impl Mapper for Mapper71 {
+    fn cpu_read(&self, addr: u16) -> Option<u8> {
+        match addr {
+            0x8000..=0xFFFF => self.prg_rom.get(self.prg_rom_index(addr)).copied(),
+            _ => Some(open_bus(addr)),
+        }
+    }
+
+    fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        match addr {
+            0x9000..=0x9FFF if self.one_screen_select => {
+                self.mirroring = if value & 0x10 != 0 {
+                    ScreenMirroring::SingleUpper
+                } else {
+                    ScreenMirroring::SingleLower
+                };
+                true
+            }
+            0xC000..=0xFFFF => {
+                self.prg_bank = value & 0x0F;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn ppu_read(&self, addr: u16) -> Option<u8> {
+        self.chr.read((addr & 0x1FFF) as usize)
+    }
+
+    fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
+        self.chr.write((addr & 0x1FFF) as usize, data)
+    }
+
+    fn mirroring(&self) -> Option<ScreenMirroring> {
+        Some(self.mirroring)
+    }
+
+    fn box_clone(&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn reset(&mut self) {
+        self.prg_bank = 0;
+        self.chr.reset();
+    }
+}
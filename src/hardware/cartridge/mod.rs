@@ -1,25 +1,52 @@
+mod archive;
+mod error;
+mod fds;
 mod mapper;
+mod mapper1;
+mod mapper11;
+mod mapper2;
+mod mapper3;
+mod mapper34;
+mod mapper4;
+mod mapper66;
+mod mapper7;
+mod mapper71;
+mod registry;
+mod rom_info;
+mod unif;
+pub use archive::unpack;
+pub use error::{CartridgeError, RomSection};
 use mapper::{Mapper, Mapper0};
-use std::io::{Read, Seek, SeekFrom};
-
-struct Header {
-    pub prg_rom_size: u8,
-    pub chr_rom_size: u8,
-    pub mapper1: u8,
-    pub mapper2: u8,
-    pub prg_ram_size: u8,
-    pub tv_system1: u8,
-    pub tv_system2: u8,
-}
+use registry::MapperConfig;
+use rom_info::RomFormat;
+pub use rom_info::RomInfo;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ScreenMirroring {
-    Single,
+    SingleLower,
+    SingleUpper,
     Vertical,
+    #[default]
     Horizontal,
     FourScreen,
 }
 
+impl ScreenMirroring {
+    pub fn nametable_index(self, addr: u16) -> usize {
+        // Map $2000-$2FFF onto the two 1KB pages of CIRAM
+        let offset = (addr & 0x0FFF) as usize;
+        let table = offset / 0x400;
+        let page = match self {
+            ScreenMirroring::SingleLower => 0,
+            ScreenMirroring::SingleUpper => 1,
+            ScreenMirroring::Vertical => table & 0x01,
+            ScreenMirroring::Horizontal => table >> 1,
+            ScreenMirroring::FourScreen => table & 0x01, // $2800-$2FFF are on the cartridge
+        };
+        page * 0x400 + (offset & 0x3FF)
+    }
+}
+
 impl Default for Cartridge {
     fn default() -> Self {
         Cartridge::new()
@@ -30,83 +57,380 @@ impl Default for Cartridge {
 pub struct Cartridge {
     pub mapper: Box<dyn Mapper>,
     pub mirroring: ScreenMirroring,
+    pub info: RomInfo,
+    pub fds_bios: Option<Vec<u8>>, // Needed to boot disk images
+    four_screen_ram: Vec<u8>,      // Extra 2KB for the upper nametables of four-screen boards
+    trainer: Option<Vec<u8>>,
 }
 
 impl Cartridge {
     pub fn new() -> Self {
         Cartridge {
-            mapper: Box::new(Mapper0::new(vec![], vec![], false)),
-            mirroring: ScreenMirroring::Single,
+            mapper: Box::new(Mapper0::new(&RomInfo::default(), vec![], vec![])),
+            mirroring: ScreenMirroring::SingleLower,
+            info: RomInfo::default(),
+            fds_bios: None,
+            four_screen_ram: Vec::new(),
+            trainer: None,
         }
     }
 
-    pub fn load_ines_rom(&mut self, file_path: &str) -> Result<(), std::io::Error> {
-        // Load the INES ROM header and data
-        // Open the file
-        let mut file = std::fs::File::open(file_path)?;
+    pub fn set_fds_bios(&mut self, bios: Vec<u8>) -> Result<(), CartridgeError> {
+        if bios.len() != fds::BIOS_SIZE {
+            return Err(CartridgeError::InvalidFdsBios { size: bios.len() });
+        }
+        self.fds_bios = Some(bios);
+        Ok(())
+    }
 
-        let mut header_bytes = [0u8; 16];
-        file.read_exact(&mut header_bytes)?;
-        if &header_bytes[0..4] != b"NES\x1A" {
-            panic!("Invalid NES ROM file");
+    pub fn load_bytes(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        // Load a ROM image, keeping the FDS BIOS. The format is
+        // picked by magic bytes, anything else is treated as iNES
+        if data.starts_with(unif::UNIF_MAGIC) {
+            return self.load_unif(data);
+        }
+        if fds::is_disk_image(data) {
+            return self.load_fds(data);
         }
 
-        let header = Header {
-            prg_rom_size: header_bytes[4] as u8,
-            chr_rom_size: header_bytes[5] as u8,
-            mapper1: header_bytes[6] as u8,
-            mapper2: header_bytes[7] as u8,
-            prg_ram_size: header_bytes[8] as u8,
-            tv_system1: header_bytes[9] as u8,
-            tv_system2: header_bytes[10] as u8,
-        };
-        // Process the header and load PRG and CHR ROMs
-        // This is a simplified example; actual implementation may vary
+        let mut offset = 0;
 
-        if header.mapper1 & 0x04 != 0 {
-            file.seek(SeekFrom::Current(512))?;
+        let header_bytes: [u8; 16] = Self::take(data, &mut offset, 16, RomSection::Header)?
+            .try_into()
+            .unwrap();
+        if &header_bytes[0..4] != b"NES\x1A" {
+            let mut magic = [0u8; 4];
+            magic.copy_from_slice(&header_bytes[0..4]);
+            return Err(CartridgeError::BadMagic(magic));
         }
 
-        let mapper: u8 = (header.mapper1 >> 4) | (header.mapper2 & 0xF0);
+        let info = RomInfo::parse(&header_bytes);
 
-        let mirroring = if header.mapper1 & 0b1000 != 0 {
-            ScreenMirroring::FourScreen
-        } else if header.mapper1 & 0b1 != 0 {
-            ScreenMirroring::Vertical
+        let trainer = if info.trainer {
+            Some(Self::take(data, &mut offset, 512, RomSection::Trainer)?)
         } else {
-            ScreenMirroring::Horizontal
+            None
         };
 
-        let mut prg_rom = vec![0u8; (header.prg_rom_size as usize) * 16 * 1024];
-
-        file.read_exact(&mut prg_rom)?;
+        let prg_rom =
+            Self::take(data, &mut offset, info.prg_rom_size, RomSection::PrgRom)?.to_vec();
 
-        let mut chr_rom: Vec<u8>;
-        if header.chr_rom_size > 0 {
-            chr_rom = vec![0u8; (header.chr_rom_size as usize) * 8 * 1024];
+        let chr_rom = if info.chr_rom_size > 0 {
+            Self::take(data, &mut offset, info.chr_rom_size, RomSection::ChrRom)?.to_vec()
         } else {
             // If CHR ROM size is 0, we can use CHR RAM
-            chr_rom = vec![0u8; 8192]; // 8 KB of CHR RAM
-        }
-        file.read_exact(&mut chr_rom)?;
-
-        let mapper = match mapper {
-            0 => Box::new(Mapper0::new(
-                prg_rom.clone(),
-                chr_rom.clone(),
-                header.chr_rom_size == 0,
-            )),
-            _ => panic!("Unsupported mapper: {}", mapper),
+            vec![0u8; info.chr_ram_total().max(8 * 1024)]
+        };
+
+        self.install(info, prg_rom, chr_rom, trainer)
+    }
+
+    fn load_unif(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        let image = unif::parse(data)?;
+        let chr = if image.chr_rom.is_empty() {
+            vec![0u8; 8 * 1024]
+        } else {
+            image.chr_rom
         };
+        self.install(image.info, image.prg_rom, chr, None)
+    }
+
+    fn load_fds(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        let bios = self
+            .fds_bios
+            .clone()
+            .ok_or(CartridgeError::MissingFdsBios)?;
+        let sides = fds::parse_sides(data)?;
+        let mapper = Box::new(fds::Fds::new(bios, sides));
 
+        self.mirroring = mapper.mirroring().unwrap_or_default();
+        self.four_screen_ram = Vec::new();
+        self.trainer = None;
         self.mapper = mapper;
-        self.mirroring = mirroring;
+        self.info = RomInfo {
+            format: RomFormat::Fds,
+            mapper: 20,
+            board: String::from("FDS"),
+            prg_ram_size: 32 * 1024,
+            chr_ram_size: 8 * 1024,
+            mirroring: self.mirroring,
+            ..RomInfo::default()
+        };
+        Ok(())
+    }
+
+    fn install(
+        &mut self,
+        mut info: RomInfo,
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        trainer: Option<&[u8]>,
+    ) -> Result<(), CartridgeError> {
+        // Build the board for a parsed cartridge image
+        let entry = registry::find(info.mapper).ok_or(CartridgeError::UnsupportedMapper {
+            id: info.mapper,
+            name: registry::known_name(info.mapper),
+        })?;
+        if info.board.is_empty() {
+            info.board = entry.name.to_string();
+        }
+        let mapper = (entry.build)(MapperConfig {
+            info: &info,
+            prg_rom,
+            chr: chr_rom,
+        });
+
+        self.mirroring = mapper.mirroring().unwrap_or(info.mirroring);
+        self.four_screen_ram = if info.mirroring == ScreenMirroring::FourScreen {
+            vec![0u8; 2 * 1024]
+        } else {
+            Vec::new()
+        };
+        self.mapper = mapper;
+        self.info = info;
+        self.trainer = trainer.map(<[u8]>::to_vec);
+        self.apply_trainer();
         Ok(())
     }
 
+    pub fn apply_trainer(&mut self) {
+        // The trainer lives at $7000-$71FF, which is offset 0x1000 into PRG-RAM.
+        // Applied again after a save file is loaded, since it is copied in at boot
+        let Some(trainer) = self.trainer.as_ref() else {
+            return;
+        };
+        match self
+            .mapper
+            .prg_ram_mut()
+            .and_then(|ram| ram.get_mut(0x1000..0x1200))
+        {
+            Some(ram) => {
+                ram.copy_from_slice(trainer);
+                self.info.trainer_loaded = true;
+            }
+            None => eprintln!(
+                "Trainer ignored, mapper {} has no PRG-RAM",
+                self.info.mapper
+            ),
+        }
+    }
+
+    fn take<'a>(
+        data: &'a [u8],
+        offset: &mut usize,
+        size: usize,
+        section: RomSection,
+    ) -> Result<&'a [u8], CartridgeError> {
+        // Next `size` bytes of the image, or how many were actually left
+        let remaining = data.len().saturating_sub(*offset);
+        if remaining < size {
+            return Err(CartridgeError::Truncated {
+                section,
+                expected: size,
+                got: remaining,
+            });
+        }
+        let bytes = &data[*offset..*offset + size];
+        *offset += size;
+        Ok(bytes)
+    }
+
+    fn four_screen_index(&self, addr: u16) -> Option<usize> {
+        // Four-screen boards answer for $2800-$2FFF, CIRAM keeps the first two tables
+        let offset = (addr & 0x0FFF) as usize;
+        if self.mirroring != ScreenMirroring::FourScreen || offset < 0x800 {
+            return None;
+        }
+        Some(offset - 0x800).filter(|&index| index < self.four_screen_ram.len())
+    }
+
+    pub fn nametable_read(&self, addr: u16) -> Option<u8> {
+        // None means the access goes to CIRAM
+        self.mapper
+            .nametable_read(addr)
+            .or_else(|| Some(self.four_screen_ram[self.four_screen_index(addr)?]))
+    }
+
+    pub fn nametable_write(&mut self, addr: u16, value: u8) -> bool {
+        if self.mapper.nametable_write(addr, value) {
+            return true;
+        }
+        match self.four_screen_index(addr) {
+            Some(index) => {
+                self.four_screen_ram[index] = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, value: u8) -> bool {
+        let written = self.mapper.cpu_write(addr, value);
+        if let Some(mirroring) = self.mapper.mirroring() {
+            self.mirroring = mirroring;
+        }
+        written
+    }
+
     pub fn reset(&mut self) {
         // Reset the cartridge state if needed
         // For now, we just reset the mapper
         self.mapper.reset();
+        self.four_screen_ram.fill(0);
+        if let Some(mirroring) = self.mapper.mirroring() {
+            self.mirroring = mirroring;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::{DeflateEncoder, GzEncoder};
+    use std::io::Write;
+
+    fn nrom_image() -> Vec<u8> {
+        // 16KB PRG filled with its bank offset, 8KB CHR
+        let mut data = b"NES\x1A\x01\x01\x00\x00".to_vec();
+        data.resize(16, 0);
+        data.extend((0..0x4000).map(|i| (i >> 8) as u8));
+        data.extend(vec![0xCC; 0x2000]);
+        data
+    }
+
+    fn zip_archive(name: &str, contents: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(contents).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut zip = Vec::new();
+        zip.extend(0x04034B50u32.to_le_bytes());
+        zip.extend([20, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        zip.extend((compressed.len() as u32).to_le_bytes());
+        zip.extend((contents.len() as u32).to_le_bytes());
+        zip.extend((name.len() as u16).to_le_bytes());
+        zip.extend([0, 0]);
+        zip.extend(name.as_bytes());
+        zip.extend(&compressed);
+
+        let directory = zip.len();
+        zip.extend(0x02014B50u32.to_le_bytes());
+        zip.extend([20, 0, 20, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        zip.extend((compressed.len() as u32).to_le_bytes());
+        zip.extend((contents.len() as u32).to_le_bytes());
+        zip.extend((name.len() as u16).to_le_bytes());
+        zip.extend([0; 12]);
+        zip.extend(0u32.to_le_bytes()); // Local header offset
+        zip.extend(name.as_bytes());
+        let directory_size = zip.len() - directory;
+
+        zip.extend(0x06054B50u32.to_le_bytes());
+        zip.extend([0, 0, 0, 0, 1, 0, 1, 0]);
+        zip.extend((directory_size as u32).to_le_bytes());
+        zip.extend((directory as u32).to_le_bytes());
+        zip.extend([0, 0]);
+        zip
+    }
+
+    fn unif_image(board: &str) -> Vec<u8> {
+        // 64KB of PRG, CHR-RAM and vertical mirroring
+        let mut data = b"UNIF".to_vec();
+        data.extend(7u32.to_le_bytes());
+        data.resize(32, 0);
+        for (id, chunk) in [
+            (b"MAPR", board.as_bytes().to_vec()),
+            (b"PRG0", vec![0xEA; 0x10000]),
+            (b"MIRR", vec![1]),
+        ] {
+            data.extend(id);
+            data.extend((chunk.len() as u32).to_le_bytes());
+            data.extend(chunk);
+        }
+        data
+    }
+
+    fn load(data: &[u8]) -> Result<Cartridge, CartridgeError> {
+        let mut cartridge = Cartridge::new();
+        cartridge.load_bytes(&unpack(data)?)?;
+        Ok(cartridge)
+    }
+
+    fn assert_nrom(cartridge: &Cartridge) {
+        assert_eq!(cartridge.info.mapper, 0);
+        assert_eq!(cartridge.info.prg_rom_size, 0x4000);
+        assert_eq!(cartridge.mapper.cpu_read(0x8123), Some(0x01));
+        assert_eq!(cartridge.mapper.cpu_read(0xC123), Some(0x01)); // 16KB is mirrored
+        assert_eq!(cartridge.mapper.ppu_read(0x0000), Some(0xCC));
+    }
+
+    #[test]
+    fn loads_raw_ines() {
+        assert_nrom(&load(&nrom_image()).unwrap());
+    }
+
+    #[test]
+    fn loads_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&nrom_image()).unwrap();
+        assert_nrom(&load(&encoder.finish().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn loads_zip() {
+        assert_nrom(&load(&zip_archive("Game.NES", &nrom_image())).unwrap());
+    }
+
+    #[test]
+    fn zip_without_rom_is_rejected() {
+        let zip = zip_archive("readme.txt", b"hello");
+        assert!(matches!(load(&zip), Err(CartridgeError::NoRomInArchive)));
+    }
+
+    #[test]
+    fn truncated_image_is_rejected() {
+        let image = nrom_image();
+        assert!(matches!(
+            load(&image[..0x3000]),
+            Err(CartridgeError::Truncated {
+                section: RomSection::PrgRom,
+                expected: 0x4000,
+                got: 0x2FF0,
+            })
+        ));
+        assert!(matches!(
+            load(&image[..8]),
+            Err(CartridgeError::Truncated {
+                section: RomSection::Header,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn bad_magic_is_rejected() {
+        let mut image = nrom_image();
+        image[..4].copy_from_slice(b"NOPE");
+        assert!(matches!(load(&image), Err(CartridgeError::BadMagic(magic)) if &magic == b"NOPE"));
+    }
+
+    #[test]
+    fn unif_bf9097_selects_one_screen_mirroring() {
+        let mut cartridge = load(&unif_image("CAMERICA-BF9097")).unwrap();
+        assert_eq!(cartridge.info.mapper, 71);
+        assert_eq!(cartridge.info.submapper, 1);
+        assert_eq!(cartridge.mirroring, ScreenMirroring::Vertical);
+        cartridge.cpu_write(0x9000, 0x10);
+        assert_eq!(cartridge.mirroring, ScreenMirroring::SingleUpper);
+        cartridge.cpu_write(0x9000, 0x00);
+        assert_eq!(cartridge.mirroring, ScreenMirroring::SingleLower);
+    }
+
+    #[test]
+    fn unif_bf9093_keeps_header_mirroring() {
+        let mut cartridge = load(&unif_image("BF9093")).unwrap();
+        assert_eq!(cartridge.info.submapper, 0);
+        cartridge.cpu_write(0x9000, 0x10);
+        assert_eq!(cartridge.mirroring, ScreenMirroring::Vertical);
     }
 }
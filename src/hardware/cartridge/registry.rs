@@ -0,0 +1,99 @@
+use super::mapper::{Mapper, Mapper0};
+use super::mapper1::Mapper1;
+use super::mapper2::Mapper2;
+use super::mapper3::Mapper3;
+use super::mapper4::Mapper4;
+use super::mapper7::Mapper7;
+use super::mapper11::Mapper11;
+use super::mapper34::Mapper34;
+use super::mapper66::Mapper66;
+use super::mapper71::Mapper71;
+use super::rom_info::RomInfo;
+
+// Everything a board needs from the ROM image to be constructed
+pub struct MapperConfig<'a> {
+    pub info: &'a RomInfo,
+    pub prg_rom: Vec<u8>,
+    pub chr: Vec<u8>,
+}
+
+pub struct MapperEntry {
+    pub id: u16,
+    pub name: &'static str,
+    pub build: fn(MapperConfig) -> Box<dyn Mapper>,
+}
+
+// New boards only need an entry here
+const MAPPERS: &[MapperEntry] = &[
+    MapperEntry {
+        id: 0,
+        name: "NROM",
+        build: |c| Box::new(Mapper0::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 1,
+        name: "MMC1",
+        build: |c| Box::new(Mapper1::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 2,
+        name: "UxROM",
+        build: |c| Box::new(Mapper2::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 3,
+        name: "CNROM",
+        build: |c| Box::new(Mapper3::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 4,
+        name: "MMC3",
+        build: |c| Box::new(Mapper4::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 7,
+        name: "AxROM",
+        build: |c| Box::new(Mapper7::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 11,
+        name: "Color Dreams",
+        build: |c| Box::new(Mapper11::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 34,
+        name: "BNROM/NINA-001",
+        build: |c| Box::new(Mapper34::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 66,
+        name: "GxROM",
+        build: |c| Box::new(Mapper66::new(c.info, c.prg_rom, c.chr)),
+    },
+    MapperEntry {
+        id: 71,
+        name: "Camerica",
+        build: |c| Box::new(Mapper71::new(c.info, c.prg_rom, c.chr)),
+    },
+];
+
+pub fn find(id: u16) -> Option<&'static MapperEntry> {
+    MAPPERS.iter().find(|entry| entry.id == id)
+}
+
+pub fn known_name(id: u16) -> Option<&'static str> {
+    // Names of common boards we can't run yet, for error messages
+    match id {
+        5 => Some("MMC5"),
+        9 => Some("MMC2"),
+        10 => Some("MMC4"),
+        16 => Some("Bandai FCG"),
+        19 => Some("Namco 163"),
+        21 | 23 | 25 => Some("VRC4"),
+        22 => Some("VRC2"),
+        24 | 26 => Some("VRC6"),
+        69 => Some("Sunsoft FME-7"),
+        85 => Some("VRC7"),
+        _ => None,
+    }
+}
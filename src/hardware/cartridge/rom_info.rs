@@ -0,0 +1,179 @@
+use super::ScreenMirroring;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RomFormat {
+    #[default]
+    INes,
+    Nes20,
+    Unif,
+    Fds,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimingMode {
+    #[default]
+    Ntsc,
+    Pal,
+    MultiRegion,
+    Dendy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleType {
+    #[default]
+    Nes,
+    VsSystem,
+    Playchoice10,
+    Extended(u8), // NES 2.0 byte 13 when the console type is 3
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RomInfo {
+    pub format: RomFormat,
+    pub mapper: u16,
+    pub submapper: u8,
+    pub board: String,       // UNIF board name, or the name of the mapper we picked
+    pub prg_rom_size: usize, // All sizes are in bytes
+    pub chr_rom_size: usize,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub mirroring: ScreenMirroring,
+    pub battery: bool,
+    pub trainer: bool,
+    pub trainer_loaded: bool, // Set once the trainer is copied to $7000, needs PRG-RAM
+    pub timing: TimingMode,
+    pub console_type: ConsoleType,
+    pub misc_roms: u8,
+    pub default_expansion_device: u8,
+}
+
+impl RomInfo {
+    pub fn parse(header: &[u8; 16]) -> RomInfo {
+        let flags6 = header[6];
+        let flags7 = header[7];
+
+        let mirroring = if flags6 & 0b1000 != 0 {
+            ScreenMirroring::FourScreen
+        } else if flags6 & 0b1 != 0 {
+            ScreenMirroring::Vertical
+        } else {
+            ScreenMirroring::Horizontal
+        };
+        let battery = flags6 & 0x02 != 0;
+        let console_type = match flags7 & 0x03 {
+            0 => ConsoleType::Nes,
+            1 => ConsoleType::VsSystem,
+            2 => ConsoleType::Playchoice10,
+            _ => ConsoleType::Extended(header[13] & 0x0F),
+        };
+
+        if flags7 & 0x0C == 0x08 {
+            // NES 2.0
+            let mapper =
+                (flags6 >> 4) as u16 | (flags7 & 0xF0) as u16 | ((header[8] & 0x0F) as u16) << 8;
+            let chr_rom_size = Self::rom_size(header[5], header[9] >> 4, 8 * 1024);
+            let chr_ram_size = Self::ram_size(header[11] & 0x0F);
+            let chr_nvram_size = Self::ram_size(header[11] >> 4);
+            RomInfo {
+                format: RomFormat::Nes20,
+                mapper,
+                submapper: header[8] >> 4,
+                board: String::new(),
+                prg_rom_size: Self::rom_size(header[4], header[9] & 0x0F, 16 * 1024),
+                chr_rom_size,
+                prg_ram_size: Self::ram_size(header[10] & 0x0F),
+                prg_nvram_size: Self::ram_size(header[10] >> 4),
+                chr_ram_size,
+                chr_nvram_size,
+                mirroring,
+                battery,
+                trainer: flags6 & 0x04 != 0,
+                trainer_loaded: false,
+                timing: match header[12] & 0x03 {
+                    0 => TimingMode::Ntsc,
+                    1 => TimingMode::Pal,
+                    2 => TimingMode::MultiRegion,
+                    _ => TimingMode::Dendy,
+                },
+                console_type,
+                misc_roms: header[14] & 0x03,
+                default_expansion_device: header[15] & 0x3F,
+            }
+        } else {
+            // Bytes 12-15 should be zero, otherwise byte 7 is probably garbage ("DiskDude!")
+            let mapper_high = if header[12..16].iter().all(|&b| b == 0) {
+                flags7 & 0xF0
+            } else {
+                0
+            };
+            let chr_rom_size = header[5] as usize * 8 * 1024;
+            // Byte 8 counts 8KB units, 0 means 8KB for compatibility
+            let prg_ram_size = (header[8].max(1) as usize) * 8 * 1024;
+            RomInfo {
+                format: RomFormat::INes,
+                mapper: ((flags6 >> 4) | mapper_high) as u16,
+                submapper: 0,
+                board: String::new(),
+                prg_rom_size: header[4] as usize * 16 * 1024,
+                chr_rom_size,
+                prg_ram_size: if battery { 0 } else { prg_ram_size },
+                prg_nvram_size: if battery { prg_ram_size } else { 0 },
+                chr_ram_size: if chr_rom_size == 0 { 8 * 1024 } else { 0 },
+                chr_nvram_size: 0,
+                mirroring,
+                battery,
+                trainer: flags6 & 0x04 != 0,
+                trainer_loaded: false,
+                timing: if header[9] & 0x01 != 0 {
+                    TimingMode::Pal
+                } else {
+                    TimingMode::Ntsc
+                },
+                console_type,
+                misc_roms: 0,
+                default_expansion_device: 0,
+            }
+        }
+    }
+
+    fn rom_size(lsb: u8, msb: u8, unit: usize) -> usize {
+        if msb == 0x0F {
+            // Exponent-multiplier notation: 2^E * (MM * 2 + 1)
+            let exponent = (lsb >> 2) as u32;
+            let multiplier = (lsb & 0x03) as usize * 2 + 1;
+            2usize.saturating_pow(exponent).saturating_mul(multiplier)
+        } else {
+            (((msb as usize) << 8) | lsb as usize) * unit
+        }
+    }
+
+    fn ram_size(shift: u8) -> usize {
+        // Shift count, 0 means none
+        if shift == 0 { 0 } else { 64 << shift }
+    }
+
+    pub fn chr_is_ram(&self) -> bool {
+        self.chr_rom_size == 0
+    }
+
+    pub fn prg_ram_total(&self) -> usize {
+        self.prg_ram_size + self.prg_nvram_size
+    }
+
+    pub fn chr_ram_total(&self) -> usize {
+        self.chr_ram_size + self.chr_nvram_size
+    }
+
+    pub fn bus_conflicts(&self) -> bool {
+        // NES 2.0 submapper 1 of mappers 2, 3 and 7 rules them out, submapper 2 confirms them
+        match (self.mapper, self.submapper) {
+            (2 | 3 | 7, 1) => false,
+            (2 | 3 | 7, 2) => true,
+            (7, _) => false, // ANROM and AOROM, the common AxROM boards, don't have them
+            (2 | 3 | 11 | 34 | 66, _) => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use super::ScreenMirroring;
+use super::error::{CartridgeError, RomSection};
+use super::rom_info::{RomFormat, RomInfo, TimingMode};
+
+pub const UNIF_MAGIC: &[u8] = b"UNIF";
+const HEADER_SIZE: usize = 32;
+
+// UNIF names the board instead of numbering it, these are the ones we have mappers for.
+// Entries are the board name, its mapper and the NES 2.0 submapper
+const BOARDS: &[(&str, u16, u8)] = &[
+    ("NROM", 0, 0),
+    ("NROM-128", 0, 0),
+    ("NROM-256", 0, 0),
+    ("RROM", 0, 0),
+    ("RROM-128", 0, 0),
+    ("SAROM", 1, 0),
+    ("SBROM", 1, 0),
+    ("SCROM", 1, 0),
+    ("SEROM", 1, 0),
+    ("SFROM", 1, 0),
+    ("SGROM", 1, 0),
+    ("SHROM", 1, 0),
+    ("SJROM", 1, 0),
+    ("SKROM", 1, 0),
+    ("SLROM", 1, 0),
+    ("SL1ROM", 1, 0),
+    ("SNROM", 1, 0),
+    ("SOROM", 1, 0),
+    ("SUROM", 1, 0),
+    ("SXROM", 1, 0),
+    ("UNROM", 2, 0),
+    ("UOROM", 2, 0),
+    ("CNROM", 3, 0),
+    ("TBROM", 4, 0),
+    ("TEROM", 4, 0),
+    ("TFROM", 4, 0),
+    ("TGROM", 4, 0),
+    ("TKROM", 4, 0),
+    ("TLROM", 4, 0),
+    ("TL1ROM", 4, 0),
+    ("TR1ROM", 4, 0),
+    ("TSROM", 4, 0),
+    ("TVROM", 4, 0),
+    ("ANROM", 7, 0),
+    ("AN1ROM", 7, 0),
+    ("AMROM", 7, 0),
+    ("AOROM", 7, 0),
+    ("COLORDREAMS", 11, 0),
+    ("BNROM", 34, 0),
+    ("NINA-001", 34, 0),
+    ("GNROM", 66, 0),
+    ("MHROM", 66, 0),
+    ("BF9093", 71, 0),
+    ("BF9097", 71, 1),
+    ("CAMERICA-BF9093", 71, 0),
+    ("CAMERICA-BF9097", 71, 1),
+];
+
+pub struct UnifImage {
+    pub info: RomInfo,
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>,
+}
+
+pub fn board_mapper(board: &str) -> Option<(u16, u8)> {
+    // Board names usually carry a NES-/UNL-/HVC-/BTL- prefix that we don't care about
+    let name = ["NES-", "UNL-", "HVC-", "BTL-"]
+        .iter()
+        .find_map(|prefix| board.strip_prefix(prefix))
+        .unwrap_or(board);
+    BOARDS
+        .iter()
+        .find(|(known, _, _)| known.eq_ignore_ascii_case(name))
+        .map(|&(_, id, submapper)| (id, submapper))
+}
+
+pub fn parse(data: &[u8]) -> Result<UnifImage, CartridgeError> {
+    if data.len() < HEADER_SIZE {
+        return Err(CartridgeError::Truncated {
+            section: RomSection::Header,
+            expected: HEADER_SIZE,
+            got: data.len(),
+        });
+    }
+
+    let mut board = None;
+    let mut prg_chunks: [Option<&[u8]>; 16] = [None; 16];
+    let mut chr_chunks: [Option<&[u8]>; 16] = [None; 16];
+    let mut mirroring = None;
+    let mut battery = false;
+    let mut timing = TimingMode::Ntsc;
+
+    // Chunks are a 4 byte id, a little endian length and the data
+    let mut offset = HEADER_SIZE;
+    while offset + 8 <= data.len() {
+        let id: [u8; 4] = data[offset..offset + 4].try_into().unwrap();
+        let length = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap()) as usize;
+        let start = offset + 8;
+        let chunk =
+            data.get(start..start.saturating_add(length))
+                .ok_or(CartridgeError::Truncated {
+                    section: RomSection::UnifChunk(id),
+                    expected: length,
+                    got: data.len() - start,
+                })?;
+        offset = start + length;
+
+        let bank = (id[3] as char).to_digit(16).unwrap_or(0) as usize;
+        match &id {
+            b"MAPR" => {
+                let name = chunk.split(|&b| b == 0).next().unwrap_or_default();
+                board = Some(String::from_utf8_lossy(name).trim().to_string());
+            }
+            [b'P', b'R', b'G', _] => prg_chunks[bank] = Some(chunk),
+            [b'C', b'H', b'R', _] => chr_chunks[bank] = Some(chunk),
+            b"MIRR" => {
+                mirroring = match chunk.first() {
+                    Some(0) => Some(ScreenMirroring::Horizontal),
+                    Some(1) => Some(ScreenMirroring::Vertical),
+                    Some(2) => Some(ScreenMirroring::SingleLower),
+                    Some(3) => Some(ScreenMirroring::SingleUpper),
+                    Some(4) => Some(ScreenMirroring::FourScreen),
+                    _ => None, // 5 means the mapper controls it
+                }
+            }
+            b"BATR" => battery = chunk.first().is_some_and(|&b| b != 0),
+            b"TVCI" => {
+                timing = match chunk.first() {
+                    Some(1) => TimingMode::Pal,
+                    Some(2) => TimingMode::MultiRegion,
+                    _ => TimingMode::Ntsc,
+                }
+            }
+            _ => {} // NAME, READ, DINF, CTRL and CRC chunks are informational
+        }
+    }
+
+    let board =
+        board.ok_or_else(|| CartridgeError::UnsupportedBoard(String::from("(no MAPR chunk)")))?;
+    let (mapper, submapper) =
+        board_mapper(&board).ok_or_else(|| CartridgeError::UnsupportedBoard(board.clone()))?;
+
+    // PRG0..PRGF and CHR0..CHRF are concatenated in bank order
+    let prg_rom: Vec<u8> = prg_chunks
+        .iter()
+        .flatten()
+        .flat_map(|c| c.iter())
+        .copied()
+        .collect();
+    let chr_rom: Vec<u8> = chr_chunks
+        .iter()
+        .flatten()
+        .flat_map(|c| c.iter())
+        .copied()
+        .collect();
+    if prg_rom.is_empty() {
+        return Err(CartridgeError::Truncated {
+            section: RomSection::PrgRom,
+            expected: 16 * 1024,
+            got: 0,
+        });
+    }
+
+    // UNIF has no RAM sizes, assume the usual 8KB of PRG-RAM
+    let prg_ram_size = 8 * 1024;
+    let info = RomInfo {
+        format: RomFormat::Unif,
+        mapper,
+        submapper,
+        board,
+        prg_rom_size: prg_rom.len(),
+        chr_rom_size: chr_rom.len(),
+        prg_ram_size: if battery { 0 } else { prg_ram_size },
+        prg_nvram_size: if battery { prg_ram_size } else { 0 },
+        chr_ram_size: if chr_rom.is_empty() { 8 * 1024 } else { 0 },
+        mirroring: mirroring.unwrap_or_default(),
+        battery,
+        timing,
+        ..RomInfo::default()
+    };
+
+    Ok(UnifImage {
+        info,
+        prg_rom,
+        chr_rom,
+    })
+}
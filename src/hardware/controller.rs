@@ -0,0 +1,85 @@
+use super::enums::Buttons;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ButtonState {
+    pub a: bool,
+    pub b: bool,
+    pub select: bool,
+    pub start: bool,
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl ButtonState {
+    pub fn set(&mut self, button: Buttons, pressed: bool) {
+        match button {
+            Buttons::A => self.a = pressed,
+            Buttons::B => self.b = pressed,
+            Buttons::Select => self.select = pressed,
+            Buttons::Start => self.start = pressed,
+            Buttons::Up => self.up = pressed,
+            Buttons::Down => self.down = pressed,
+            Buttons::Left => self.left = pressed,
+            Buttons::Right => self.right = pressed,
+        }
+    }
+
+    fn to_bits(self) -> u8 {
+        // Bit order matches the order buttons are shifted out: A first, Right last
+        (self.a as u8)
+            | (self.b as u8) << 1
+            | (self.select as u8) << 2
+            | (self.start as u8) << 3
+            | (self.up as u8) << 4
+            | (self.down as u8) << 5
+            | (self.left as u8) << 6
+            | (self.right as u8) << 7
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct Controller {
+    buttons: u8,
+    shift: u8,
+    strobe: bool,
+}
+
+impl Controller {
+    pub fn new() -> Self {
+        Controller {
+            buttons: 0,
+            shift: 0,
+            strobe: false,
+        }
+    }
+
+    pub fn set_buttons(&mut self, state: ButtonState) {
+        self.buttons = state.to_bits();
+        if self.strobe {
+            self.shift = self.buttons;
+        }
+    }
+
+    pub fn write(&mut self, value: u8) {
+        // While strobe is high the shift register keeps reloading from the buttons
+        self.strobe = value & 0x01 != 0;
+        if self.strobe {
+            self.shift = self.buttons;
+        }
+    }
+
+    pub fn read(&mut self) -> u8 {
+        let bit = if self.strobe {
+            self.buttons & 0x01
+        } else {
+            let bit = self.shift & 0x01;
+            // Official controllers return 1 after all 8 buttons have been read
+            self.shift = (self.shift >> 1) | 0x80;
+            bit
+        };
+        // Upper bits are open bus, usually the high byte of $4016/$4017
+        bit | 0x40
+    }
+}
@@ -1,10 +1,10 @@
 use std::fmt::{self, Display, Formatter};
 
-use crate::hardware::bus::{Bus, ReadAddressWithModeResult};
+use crate::hardware::bus::Bus;
 
-use super::Registers;
+use super::{CPU, Registers};
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddressMode {
     Implicit,
     Immediate,
@@ -21,6 +21,26 @@ pub enum AddressMode {
     IndirectY,
 }
 
+impl AddressMode {
+    // Instruction length in bytes, opcode included
+    pub const fn instruction_len(self) -> u8 {
+        match self {
+            AddressMode::Implicit | AddressMode::Accumulator => 1,
+            AddressMode::Immediate
+            | AddressMode::ZeroPage
+            | AddressMode::ZeroPageX
+            | AddressMode::ZeroPageY
+            | AddressMode::Relative
+            | AddressMode::IndirectX
+            | AddressMode::IndirectY => 2,
+            AddressMode::Absolute
+            | AddressMode::AbsoluteX
+            | AddressMode::AbsoluteY
+            | AddressMode::Indirect => 3,
+        }
+    }
+}
+
 impl Display for AddressMode {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -44,7 +64,7 @@ impl Display for AddressMode {
 fn log_instruct(instruct_name: &str, address_mode: &AddressMode, bus: Option<&mut Bus>) {
     // match bus {
     //     Some(b) => {
-    //         let (instruct, _) = b.get_instruction_text(address_mode, None);
+    //         let instruct = b.get_instruction_text(address_mode, None);
     //         println!(
     //             "{0:x}: {1} - [ {2} {3} ]",
     //             b.cpu.get_counter(),
@@ -59,367 +79,246 @@ fn log_instruct(instruct_name: &str, address_mode: &AddressMode, bus: Option<&mu
     // }
 }
 
-pub fn adc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn adc(bus: &mut Bus, address_mode: AddressMode) {
     // Add with carry
     log_instruct("ADC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
+    let value = bus.read_operand(&address_mode);
     let result: u16 =
-        (val.value as u16) + (bus.cpu.get(Registers::A) as u16) + (bus.cpu.get_carry() as u16);
+        (value as u16) + (bus.cpu.get(Registers::A) as u16) + (bus.cpu.get_carry() as u16);
     bus.cpu.set_carry(result > 0xFF);
     bus.cpu.set_zero(result & 0xFF == 0);
     bus.cpu.set_overflow(
-        ((bus.cpu.get(Registers::A) ^ result as u8) & (val.value ^ result as u8) & 0x80) != 0,
+        ((bus.cpu.get(Registers::A) ^ result as u8) & (value ^ result as u8) & 0x80) != 0,
     );
     bus.cpu.set_negative(result & 0x80 != 0);
     bus.cpu.set(Registers::A, result as u8);
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn and(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+}
+
+pub fn and(bus: &mut Bus, address_mode: AddressMode) {
     // Logical AND
     log_instruct("AND", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let result = bus.cpu.get(Registers::A) & val.value;
+    let value = bus.read_operand(&address_mode);
+    let result = bus.cpu.get(Registers::A) & value;
     bus.cpu.set_zero(result == 0);
     bus.cpu.set_negative(result & 0x80 != 0);
     bus.cpu.set(Registers::A, result);
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn asl(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+}
+
+pub fn asl(bus: &mut Bus, address_mode: AddressMode) {
     // Arithmetic shift left
     log_instruct("ASL", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let result = val.value << 1;
-    bus.cpu.set_carry(val.value & 0x80 != 0);
-    bus.cpu.set_zero(result == 0);
-    bus.cpu.set_negative(result & 0x80 != 0);
-    if address_mode == AddressMode::Accumulator {
-        bus.cpu.set(Registers::A, result);
-    } else {
-        bus.write(val.address, result);
-    }
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let result = value << 1;
+        cpu.set_carry(value & 0x80 != 0);
+        cpu.set_zero(result == 0);
+        cpu.set_negative(result & 0x80 != 0);
+        result
+    });
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Accumulator => 2,
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
 }
 
-pub fn bcc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
-    // Branch if carry clear
-    log_instruct("BCC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_carry() == 0 {
-        cycles += 1;
+fn branch(bus: &mut Bus, address_mode: &AddressMode, taken: bool) {
+    // 2 cycles, 3 when taken and 4 when the target is on another page
+    let offset = bus.read_next() as i8;
+    bus.increment_pc(address_mode);
+    if taken {
         let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
+        let target = pc.wrapping_add_signed(offset as i16);
+        bus.read(pc); // Next opcode, read while the offset is added
+        if pc & 0xFF00 != target & 0xFF00 {
+            bus.read((pc & 0xFF00) | (target & 0x00FF));
         }
+        bus.cpu.set_counter(target);
     }
-    bus.increment_pc(&address_mode);
-    cycles + 2
 }
 
-pub fn bcs(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bcc(bus: &mut Bus, address_mode: AddressMode) {
+    // Branch if carry clear
+    log_instruct("BCC", &address_mode, Some(bus));
+    let taken = bus.cpu.get_carry() == 0;
+    branch(bus, &address_mode, taken);
+}
+
+pub fn bcs(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if carry set
     log_instruct("BCS", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_carry() != 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_carry() != 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn beq(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn beq(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if equal
     log_instruct("BEQ", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_zero() != 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_zero() != 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn bit(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bit(bus: &mut Bus, address_mode: AddressMode) {
     // Bit test
     log_instruct("BIT", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let result = bus.cpu.get(Registers::A) & val.value;
+    let value = bus.read_operand(&address_mode);
+    let result = bus.cpu.get(Registers::A) & value;
     bus.cpu.set_zero(result == 0);
-    bus.cpu.set_overflow(val.value & 0x40 != 0);
-    bus.cpu.set_negative(val.value & 0x80 != 0);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 3,
-        AddressMode::Absolute => 4,
-        _ => 0,
-    } + val.cycles;
+    bus.cpu.set_overflow(value & 0x40 != 0);
+    bus.cpu.set_negative(value & 0x80 != 0);
     bus.increment_pc(&address_mode);
-    cycles
 }
 
-pub fn bmi(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bmi(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if minus
     log_instruct("BMI", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_negative() != 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_negative() != 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn bne(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bne(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if not Equal
     log_instruct("BNE", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_zero() == 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_zero() == 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn bpl(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bpl(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if positive
     log_instruct("BPL", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_negative() == 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_negative() == 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn brk(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn brk(bus: &mut Bus, address_mode: AddressMode) {
     // Force intrupt
     log_instruct("BRK", &address_mode, Some(bus));
-    bus.stack_push_word(bus.cpu.get_counter() + 2);
-    let p_register = bus.cpu.get(Registers::P) | !0b00110000; // Set break flag
+    bus.dummy_read(); // Padding byte
+    let p_register = bus.cpu.get(Registers::P) | 0b0011_0000; // Set break and unused bits
+    let return_address = bus.cpu.get_counter().wrapping_add(2);
+    interrupt(bus, return_address, p_register);
+}
+
+pub fn interrupt_request(bus: &mut Bus) {
+    // NMI or IRQ: two reads of the opcode it replaces, then the BRK sequence with B clear
+    let pc = bus.cpu.get_counter();
+    bus.read(pc);
+    bus.read(pc);
+    let p_register = (bus.cpu.get(Registers::P) & !0x10) | 0x20;
+    interrupt(bus, pc, p_register);
+}
+
+fn interrupt(bus: &mut Bus, return_address: u16, p_register: u8) {
+    // Pushes PC and P, then jumps through the vector: 5 cycles
+    bus.stack_push_word(return_address);
     bus.stack_push(p_register);
-    bus.cpu.set_break(true);
-    let low_byte = bus.read(0xFFFE) as u16;
-    let high_byte = bus.read(0xFFFF) as u16;
-    bus.cpu.set_counter((high_byte << 8) + low_byte);
     bus.cpu.set_interrupt_disable(true);
-    return 7;
+    // The vector is picked after the pushes, so a late NMI hijacks BRK and IRQ
+    let vector = if bus.interrupts.take_nmi() {
+        0xFFFA
+    } else {
+        0xFFFE
+    };
+    let pc = bus.read_word(vector);
+    bus.cpu.set_counter(pc);
+    bus.interrupts.finish_sequence();
 }
 
-pub fn bvc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bvc(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if Overflow clear
     log_instruct("BVC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_overflow() == 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-
-    cycles + 2
+    let taken = bus.cpu.get_overflow() == 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn bvs(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn bvs(bus: &mut Bus, address_mode: AddressMode) {
     // Branch if Overflow set
     log_instruct("BVS", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let mut cycles = val.cycles;
-    if bus.cpu.get_overflow() != 0 {
-        cycles += 1;
-        let pc = bus.cpu.get_counter();
-        bus.cpu.set_counter(val.address);
-        if pc & 0xFF00 != val.address & 0xFF00 {
-            cycles += 1;
-        }
-    }
-    bus.increment_pc(&address_mode);
-    cycles + 2
+    let taken = bus.cpu.get_overflow() != 0;
+    branch(bus, &address_mode, taken);
 }
 
-pub fn clc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn clc(bus: &mut Bus, address_mode: AddressMode) {
     // Clear Carry Flag
     log_instruct("CLC", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.cpu.set_carry(false);
-    2 // CLC takes 2 cycles
 }
 
-pub fn cld(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn cld(bus: &mut Bus, address_mode: AddressMode) {
     // Clear Decimal Mode
     log_instruct("CLD", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.cpu.set_decimal(false);
-    2 // CLD takes 2 cycles
 }
 
-pub fn cli(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn cli(bus: &mut Bus, address_mode: AddressMode) {
     // Set Interrupt Disable
     log_instruct("CLI", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
+    // Set on the last cycle, so the interrupt poll before it still sees the old flag
     bus.cpu.set_interrupt_disable(false);
-    2 // CLI takes 2 cycles
 }
 
-pub fn clv(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn clv(bus: &mut Bus, address_mode: AddressMode) {
     // Clear Overflow FLag
     log_instruct("CLV", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.cpu.set_overflow(false);
-    2 // CLV takes 2 cycles
 }
 
-pub fn cmp(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn cmp(bus: &mut Bus, address_mode: AddressMode) {
     // Compare
     log_instruct("CMP", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
+    let value = bus.read_operand(&address_mode);
     let acc = bus.cpu.a;
-    let res = acc.wrapping_sub(val.value);
+    let res = acc.wrapping_sub(value);
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative((res & 0x80) != 0);
-    bus.cpu.set_carry(acc >= val.value);
+    bus.cpu.set_carry(acc >= value);
     bus.increment_pc(&address_mode);
-
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
 }
 
-pub fn cpx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn cpx(bus: &mut Bus, address_mode: AddressMode) {
     // Compare X Register
     log_instruct("CPX", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
+    let value = bus.read_operand(&address_mode);
     let reg = bus.cpu.x;
-    let res = reg.wrapping_sub(val.value);
+    let res = reg.wrapping_sub(value);
     bus.cpu.set_zero(res == 0);
-    bus.cpu.set_carry(reg >= val.value);
+    bus.cpu.set_carry(reg >= value);
     bus.cpu.set_negative((res & 0x80) != 0);
 
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::Absolute => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
 }
 
-pub fn cpy(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn cpy(bus: &mut Bus, address_mode: AddressMode) {
     // Compare Y Register
     log_instruct("CPY", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
+    let value = bus.read_operand(&address_mode);
     let reg = bus.cpu.y;
-    let res = reg.wrapping_sub(val.value);
+    let res = reg.wrapping_sub(value);
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative((res & 0x80) != 0);
-    bus.cpu.set_carry(reg >= val.value);
+    bus.cpu.set_carry(reg >= value);
 
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::Absolute => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
 }
 
-pub fn dec(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn dec(bus: &mut Bus, address_mode: AddressMode) {
     // Decrement Memory
     log_instruct("DEC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let res = val.value.wrapping_sub(1);
-    bus.cpu.set_negative(res & 0x80 != 0);
-    bus.cpu.set_zero(res == 0);
-    bus.write(val.address, res as u8);
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value.wrapping_sub(1);
+        cpu.set_negative(res & 0x80 != 0);
+        cpu.set_zero(res == 0);
+        res
+    });
+    bus.increment_pc(&address_mode);
 }
 
-pub fn dex(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn dex(bus: &mut Bus, address_mode: AddressMode) {
     // Decrement X Register
     if address_mode != AddressMode::Implicit {
         panic!("Only Implicit address mode is acceptable for DEX!");
@@ -430,11 +329,11 @@ pub fn dex(bus: &mut Bus, address_mode: AddressMode) -> u8 {
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.x = res;
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // DEX takes 2 cycles
 }
 
-pub fn dey(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn dey(bus: &mut Bus, address_mode: AddressMode) {
     // Decrement Y Register
     if address_mode != AddressMode::Implicit {
         panic!("Only Implicit address mode is acceptable for DEY!");
@@ -445,54 +344,35 @@ pub fn dey(bus: &mut Bus, address_mode: AddressMode) -> u8 {
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.y = res;
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // DEY takes 2 cycles
 }
 
-pub fn eor(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn eor(bus: &mut Bus, address_mode: AddressMode) {
     // Exclusive OR
     log_instruct("EOR", &address_mode, Some(bus));
     let acc = bus.cpu.a;
-    let val = bus.read_address_with_mode(&address_mode);
-    let res = acc ^ val.value;
+    let value = bus.read_operand(&address_mode);
+    let res = acc ^ value;
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.set_zero(res == 0);
     bus.cpu.a = res;
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn inc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+}
+
+pub fn inc(bus: &mut Bus, address_mode: AddressMode) {
     // Increment Memory
     log_instruct("INC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let res = val.value.wrapping_add(1);
-    bus.cpu.set_zero(res == 0);
-    bus.cpu.set_negative(res & 0x80 != 0);
-    bus.write(val.address, res);
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value.wrapping_add(1);
+        cpu.set_zero(res == 0);
+        cpu.set_negative(res & 0x80 != 0);
+        res
+    });
+    bus.increment_pc(&address_mode);
 }
 
-pub fn inx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn inx(bus: &mut Bus, address_mode: AddressMode) {
     // Increment X Register
     log_instruct("INX", &address_mode, Some(bus));
     let reg = bus.cpu.x;
@@ -500,11 +380,11 @@ pub fn inx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.x = res;
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // INX takes 2 cycles
 }
 
-pub fn iny(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn iny(bus: &mut Bus, address_mode: AddressMode) {
     // Increment Y Register
     log_instruct("INY", &address_mode, Some(bus));
     let reg = bus.cpu.y;
@@ -512,442 +392,548 @@ pub fn iny(bus: &mut Bus, address_mode: AddressMode) -> u8 {
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.y = res;
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // INY takes 2 cycles
 }
 
-pub fn jmp(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn jmp(bus: &mut Bus, address_mode: AddressMode) {
     // Jump
     log_instruct("JMP", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.cpu.set_counter(val.address);
-    match address_mode {
-        AddressMode::Absolute => 3,
-        AddressMode::Indirect => 5,
-        _ => 0,
-    }
+    let (address, _) = bus.operand_address(&address_mode, false);
+    bus.cpu.set_counter(address);
 }
 
-pub fn jsr(bus: &mut Bus, address_mode: AddressMode) -> u8 {
-    // Jump to Subroutine
+pub fn jsr(bus: &mut Bus, address_mode: AddressMode) {
+    // Jump to Subroutine, pushes the address of its own last byte
     log_instruct("JSR", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.increment_pc(&address_mode);
-    bus.stack_push_word(bus.cpu.get_counter());
-    bus.cpu.set_counter(val.address);
-    6 // JSR takes 6 cycles
+    let pc = bus.cpu.get_counter();
+    let low_byte = bus.read_next();
+    bus.read(0x100 + bus.cpu.get(Registers::S) as u16); // Stack read while S is prepared
+    bus.stack_push_word(pc.wrapping_add(2));
+    let high_byte = bus.read(pc.wrapping_add(2));
+    bus.cpu
+        .set_counter(((high_byte as u16) << 8) | low_byte as u16);
 }
 
-pub fn lda(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn lda(bus: &mut Bus, address_mode: AddressMode) {
     // Load Accumulator
     log_instruct("LDA", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.cpu.set_zero(val.value == 0);
-    bus.cpu.set_negative(val.value & 0x80 != 0);
-    bus.cpu.set(Registers::A, val.value);
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn ldx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+    let value = bus.read_operand(&address_mode);
+    bus.cpu.set_zero(value == 0);
+    bus.cpu.set_negative(value & 0x80 != 0);
+    bus.cpu.set(Registers::A, value);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn ldx(bus: &mut Bus, address_mode: AddressMode) {
     // Load X Register
     log_instruct("LDX", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.cpu.set_zero(val.value == 0);
-    bus.cpu.set_negative(val.value & 0x80 != 0);
-    bus.cpu.set(Registers::X, val.value);
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageY => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteY => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn ldy(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+    let value = bus.read_operand(&address_mode);
+    bus.cpu.set_zero(value == 0);
+    bus.cpu.set_negative(value & 0x80 != 0);
+    bus.cpu.set(Registers::X, value);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn ldy(bus: &mut Bus, address_mode: AddressMode) {
     // Load Y Register
     log_instruct("LDY", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.cpu.set_zero(val.value == 0);
-    bus.cpu.set_negative(val.value & 0x80 != 0);
-    bus.cpu.set(Registers::Y, val.value);
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn lsr(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+    let value = bus.read_operand(&address_mode);
+    bus.cpu.set_zero(value == 0);
+    bus.cpu.set_negative(value & 0x80 != 0);
+    bus.cpu.set(Registers::Y, value);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn lsr(bus: &mut Bus, address_mode: AddressMode) {
     // Logical Shift Right
     log_instruct("LSR", &address_mode, Some(bus));
-    let is_accumulator = address_mode == AddressMode::Accumulator;
-    let val = if is_accumulator {
-        ReadAddressWithModeResult {
-            address: 0,
-            value: bus.cpu.get(Registers::A),
-            cycles: 0,
-        }
-    } else {
-        bus.read_address_with_mode(&address_mode)
-    };
-    let res = val.value >> 1;
-    bus.cpu.set_carry(val.value & 0x01 != 0);
-    bus.cpu.set_zero(res == 0);
-    bus.cpu.set_negative(false);
-    if is_accumulator {
-        bus.cpu.set(Registers::A, res);
-    } else {
-        bus.write(val.address, res);
-    }
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value >> 1;
+        cpu.set_carry(value & 0x01 != 0);
+        cpu.set_zero(res == 0);
+        cpu.set_negative(false);
+        res
+    });
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Accumulator => 2,
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
 }
 
-pub fn nop(bus: &mut Bus, address_mode: AddressMode) -> u8 {
-    // No Operation
+pub fn nop(bus: &mut Bus, address_mode: AddressMode) {
+    // No Operation, the unofficial variants still read their operand
     log_instruct("NOP", &address_mode, Some(bus));
+    bus.read_operand(&address_mode);
     bus.increment_pc(&address_mode);
-    2 // NOP takes 2 cycles
 }
 
-pub fn ora(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn ora(bus: &mut Bus, address_mode: AddressMode) {
     // Logical Inclusive OR
     log_instruct("ORA", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    let res = bus.cpu.get(Registers::A) | val.value;
+    let value = bus.read_operand(&address_mode);
+    let res = bus.cpu.get(Registers::A) | value;
     bus.cpu.set_zero(res == 0);
     bus.cpu.set_negative(res & 0x80 != 0);
     bus.cpu.set(Registers::A, res);
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn pha(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+}
+
+pub fn pha(bus: &mut Bus, address_mode: AddressMode) {
     // Push Accumulator
     log_instruct("PHA", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.stack_push(bus.cpu.get(Registers::A));
-    3 // PHA takes 3 cycles
 }
 
-pub fn php(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn php(bus: &mut Bus, address_mode: AddressMode) {
     // Push Processor Status
     log_instruct("PHP", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     let p_register = bus.cpu.get(Registers::P) | 0b0011_0000; // Set break and unused bits
     bus.stack_push(p_register);
-    3 // PHP takes 3 cycles
 }
 
-pub fn pla(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn pla(bus: &mut Bus, address_mode: AddressMode) {
     // Pull Accumulator
     log_instruct("PLA", &address_mode, Some(bus));
+    bus.dummy_read();
+    bus.read(0x100 + bus.cpu.get(Registers::S) as u16); // Stack read while S is incremented
     let val = bus.stack_pull();
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
     bus.cpu.set(Registers::A, val);
     bus.increment_pc(&address_mode);
-    4 // PLA takes 4 cycles
 }
 
-pub fn plp(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn plp(bus: &mut Bus, address_mode: AddressMode) {
     // Pull Processor Status
     log_instruct("PLP", &address_mode, Some(bus));
+    bus.dummy_read();
+    bus.read(0x100 + bus.cpu.get(Registers::S) as u16); // Stack read while S is incremented
+    // Pulled on the last cycle, a changed I flag only affects the next poll
     let val = bus.stack_pull();
-    let interrupt_disable = val & 0b0000_0100;
-    bus.cpu.delayed_interrupt_flag = Some(interrupt_disable != 0);
-    bus.cpu.set(Registers::P, val);
+    bus.cpu.set(Registers::P, val & 0b1100_1111); // Break and unused bits don't exist in P
     bus.increment_pc(&address_mode);
-    4 // PLP takes 4 cycles
 }
 
-pub fn rol(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn rol(bus: &mut Bus, address_mode: AddressMode) {
     // Rotate Left
     log_instruct("ROL", &address_mode, Some(bus));
-    let is_accumulator = address_mode == AddressMode::Accumulator;
-    let val = if is_accumulator {
-        ReadAddressWithModeResult {
-            address: 0,
-            value: bus.cpu.get(Registers::A),
-            cycles: 0,
-        }
-    } else {
-        bus.read_address_with_mode(&address_mode)
-    };
-    let res = (val.value << 1) | bus.cpu.get_carry();
-    bus.cpu.set_carry(val.value & 0x80 != 0);
-    bus.cpu.set_zero(res == 0);
-    bus.cpu.set_negative(res & 0x80 != 0);
-    if is_accumulator {
-        bus.cpu.set(Registers::A, res);
-    } else {
-        bus.write(val.address, res);
-    }
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = (value << 1) | cpu.get_carry();
+        cpu.set_carry(value & 0x80 != 0);
+        cpu.set_zero(res == 0);
+        cpu.set_negative(res & 0x80 != 0);
+        res
+    });
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Accumulator => 2,
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
 }
 
-pub fn ror(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn ror(bus: &mut Bus, address_mode: AddressMode) {
     // Rotate Right
     log_instruct("ROR", &address_mode, Some(bus));
-    let is_accumulator = address_mode == AddressMode::Accumulator;
-    let val = if is_accumulator {
-        ReadAddressWithModeResult {
-            address: 0,
-            value: bus.cpu.get(Registers::A),
-            cycles: 0,
-        }
-    } else {
-        bus.read_address_with_mode(&address_mode)
-    };
-    let res = (val.value >> 1) | (bus.cpu.get_carry() << 7);
-    bus.cpu.set_carry(val.value & 0x01 != 0);
-    bus.cpu.set_zero(res == 0);
-    bus.cpu.set_negative(res & 0x80 != 0);
-    if is_accumulator {
-        bus.cpu.set(Registers::A, res);
-    } else {
-        bus.write(val.address, res);
-    }
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = (value >> 1) | (cpu.get_carry() << 7);
+        cpu.set_carry(value & 0x01 != 0);
+        cpu.set_zero(res == 0);
+        cpu.set_negative(res & 0x80 != 0);
+        res
+    });
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Accumulator => 2,
-        AddressMode::ZeroPage => 5,
-        AddressMode::ZeroPageX => 6,
-        AddressMode::Absolute => 6,
-        AddressMode::AbsoluteX => 7,
-        _ => 0,
-    };
-    cycles
 }
 
-pub fn rti(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn rti(bus: &mut Bus, address_mode: AddressMode) {
     // Return from Interrupt
     log_instruct("RTI", &address_mode, Some(bus));
+    bus.dummy_read();
+    bus.read(0x100 + bus.cpu.get(Registers::S) as u16); // Stack read while S is incremented
     let flags = bus.stack_pull();
     bus.cpu.set(Registers::P, flags & 0b1100_1111);
     let pc = bus.stack_pull_word();
     bus.cpu.set_counter(pc);
-    6 // RTI takes 6 cycles
 }
 
-pub fn rts(bus: &mut Bus, address_mode: AddressMode) -> u8 {
-    // Return from Subroutine
+pub fn rts(bus: &mut Bus, address_mode: AddressMode) {
+    // Return from Subroutine, JSR pushed the address of its last byte
     log_instruct("RTS", &address_mode, Some(bus));
+    bus.dummy_read();
+    bus.read(0x100 + bus.cpu.get(Registers::S) as u16); // Stack read while S is incremented
     let pc = bus.stack_pull_word();
-    bus.cpu.set_counter(pc);
-    6 // RTS takes 6 cycles
+    bus.read(pc); // Read while PC is incremented
+    bus.cpu.set_counter(pc.wrapping_add(1));
 }
 
-pub fn sbc(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn sbc(bus: &mut Bus, address_mode: AddressMode) {
     // Subtract with Carry
     log_instruct("SBC", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
+    let value = bus.read_operand(&address_mode);
     let acc = bus.cpu.get(Registers::A);
     let carry = bus.cpu.get_carry();
-    let result = acc as u16 + !val.value as u16 + carry as u16;
+    let result = acc as u16 + !value as u16 + carry as u16;
     bus.cpu.set_carry(result > 0xFF);
     bus.cpu.set_zero(result & 0xFF == 0);
     bus.cpu
-        .set_overflow(((acc ^ (result as u8)) & (!val.value ^ (result as u8)) & 0x80) != 0);
+        .set_overflow(((acc ^ (result as u8)) & (!value ^ (result as u8)) & 0x80) != 0);
     bus.cpu.set_negative(result & 0x80 != 0);
     bus.cpu.set(Registers::A, result as u8);
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::Immediate => 2,
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 4,
-        AddressMode::AbsoluteY => 4,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 5,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn sec(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+}
+
+pub fn sec(bus: &mut Bus, address_mode: AddressMode) {
     // Set Carry Flag
     log_instruct("SEC", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.cpu.set_carry(true);
-    2 // SEC takes 2 cycles
 }
 
-pub fn sed(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn sed(bus: &mut Bus, address_mode: AddressMode) {
     // Set Decimal Flag
     log_instruct("SED", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
     bus.cpu.set_decimal(true);
-    2 // SED takes 2 cycles
 }
 
-pub fn sei(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn sei(bus: &mut Bus, address_mode: AddressMode) {
     // Set Interrupt Disable
     log_instruct("SEI", &address_mode, Some(bus));
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
+    // Set on the last cycle, so the interrupt poll before it still sees the old flag
     bus.cpu.set_interrupt_disable(true);
-    2 // SEI takes 2 cycles
 }
 
-pub fn sta(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn sta(bus: &mut Bus, address_mode: AddressMode) {
     // Store Accumulator
     log_instruct("STA", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.write(val.address, bus.cpu.get(Registers::A));
-    bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        AddressMode::AbsoluteX => 5,
-        AddressMode::AbsoluteY => 5,
-        AddressMode::IndirectX => 6,
-        AddressMode::IndirectY => 6,
-        _ => 0,
-    } + val.cycles;
-    cycles
-}
-
-pub fn stx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+    bus.write_operand(&address_mode, bus.cpu.get(Registers::A));
+    bus.increment_pc(&address_mode);
+}
+
+pub fn stx(bus: &mut Bus, address_mode: AddressMode) {
     // Store X Register
     log_instruct("STX", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.write(val.address, bus.cpu.get(Registers::X));
+    bus.write_operand(&address_mode, bus.cpu.get(Registers::X));
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageY => 4,
-        AddressMode::Absolute => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
 }
 
-pub fn sty(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn sty(bus: &mut Bus, address_mode: AddressMode) {
     // Store Y Register
     log_instruct("STY", &address_mode, Some(bus));
-    let val = bus.read_address_with_mode(&address_mode);
-    bus.write(val.address, bus.cpu.get(Registers::Y));
+    bus.write_operand(&address_mode, bus.cpu.get(Registers::Y));
     bus.increment_pc(&address_mode);
-    let cycles: u8 = match address_mode {
-        AddressMode::ZeroPage => 3,
-        AddressMode::ZeroPageX => 4,
-        AddressMode::Absolute => 4,
-        _ => 0,
-    } + val.cycles;
-    cycles
 }
 
-pub fn tax(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn tax(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer Accumulator to X
     log_instruct("TAX", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::A);
     bus.cpu.set(Registers::X, val);
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // TAX takes 2 cycles
 }
 
-pub fn tay(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn tay(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer Accumulator to Y
     log_instruct("TAY", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::A);
     bus.cpu.set(Registers::Y, val);
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // TAY takes 2 cycles
 }
 
-pub fn tsx(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn tsx(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer Stack Pointer to X
     log_instruct("TSX", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::S);
     bus.cpu.set(Registers::X, val);
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // TSX takes 2 cycles
 }
 
-pub fn txa(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn txa(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer X to Accumulator
     log_instruct("TXA", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::X);
     bus.cpu.set(Registers::A, val);
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // TXA takes 2 cycles
 }
 
-pub fn txs(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn txs(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer X to Stack Pointer
     log_instruct("TXS", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::X);
     bus.cpu.set(Registers::S, val);
+    bus.dummy_read();
     bus.increment_pc(&address_mode);
-    2 // TXS takes 2 cycles
 }
 
-pub fn tya(bus: &mut Bus, address_mode: AddressMode) -> u8 {
+pub fn tya(bus: &mut Bus, address_mode: AddressMode) {
     // Transfer Y to Accumulator
     log_instruct("TYA", &address_mode, Some(bus));
     let val = bus.cpu.get(Registers::Y);
     bus.cpu.set(Registers::A, val);
     bus.cpu.set_zero(val == 0);
     bus.cpu.set_negative(val & 0x80 != 0);
+    bus.dummy_read();
+    bus.increment_pc(&address_mode);
+}
+
+// Unofficial opcodes
+
+fn set_zero_negative(cpu: &mut CPU, value: u8) {
+    cpu.set_zero(value == 0);
+    cpu.set_negative(value & 0x80 != 0);
+}
+
+fn add_to_accumulator(cpu: &mut CPU, value: u8) {
+    // ADC without the bus access, shared by RRA and ISC
+    let acc = cpu.a;
+    let result = acc as u16 + value as u16 + cpu.get_carry() as u16;
+    cpu.set_carry(result > 0xFF);
+    cpu.set_overflow(((acc ^ result as u8) & (value ^ result as u8) & 0x80) != 0);
+    cpu.a = result as u8;
+    set_zero_negative(cpu, result as u8);
+}
+
+pub fn alr(bus: &mut Bus, address_mode: AddressMode) {
+    // AND immediate then LSR A
+    log_instruct("ALR", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let and = bus.cpu.a & value;
+    let res = and >> 1;
+    bus.cpu.set_carry(and & 0x01 != 0);
+    bus.cpu.a = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn anc(bus: &mut Bus, address_mode: AddressMode) {
+    // AND immediate, carry gets bit 7 like after ASL
+    log_instruct("ANC", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let res = bus.cpu.a & value;
+    bus.cpu.a = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.cpu.set_carry(res & 0x80 != 0);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn ane(bus: &mut Bus, address_mode: AddressMode) {
+    // Unstable, A = (A | magic) & X & immediate. The magic constant varies
+    // between chips, 0xEE is what most emulators and test ROMs expect
+    log_instruct("ANE", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let res = (bus.cpu.a | 0xEE) & bus.cpu.x & value;
+    bus.cpu.a = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn arr(bus: &mut Bus, address_mode: AddressMode) {
+    // AND immediate then ROR A, C and V come from bits 6 and 5 of the result
+    log_instruct("ARR", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let and = bus.cpu.a & value;
+    let res = (and >> 1) | (bus.cpu.get_carry() << 7);
+    bus.cpu.a = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.cpu.set_carry(res & 0x40 != 0);
+    bus.cpu.set_overflow(((res >> 6) ^ (res >> 5)) & 0x01 != 0);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn axs(bus: &mut Bus, address_mode: AddressMode) {
+    // X = (A & X) - immediate, flags like CMP
+    log_instruct("AXS", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let and = bus.cpu.a & bus.cpu.x;
+    let res = and.wrapping_sub(value);
+    bus.cpu.set_carry(and >= value);
+    bus.cpu.x = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn dcp(bus: &mut Bus, address_mode: AddressMode) {
+    // DEC memory then CMP
+    log_instruct("DCP", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value.wrapping_sub(1);
+        let diff = cpu.a.wrapping_sub(res);
+        cpu.set_carry(cpu.a >= res);
+        set_zero_negative(cpu, diff);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn isc(bus: &mut Bus, address_mode: AddressMode) {
+    // INC memory then SBC
+    log_instruct("ISC", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value.wrapping_add(1);
+        add_to_accumulator(cpu, !res);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn kil(bus: &mut Bus, address_mode: AddressMode) {
+    // Jams the CPU, only a reset gets it going again. The PC is left on
+    // the opcode and Hardware applies the configured JamPolicy
+    log_instruct("KIL", &address_mode, Some(bus));
+    bus.dummy_read();
+    bus.cpu.jammed = true;
+}
+
+pub fn las(bus: &mut Bus, address_mode: AddressMode) {
+    // A, X and S = memory & S
+    log_instruct("LAS", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let res = value & bus.cpu.s;
+    bus.cpu.a = res;
+    bus.cpu.x = res;
+    bus.cpu.s = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn lax(bus: &mut Bus, address_mode: AddressMode) {
+    // LDA and LDX in one
+    log_instruct("LAX", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    bus.cpu.a = value;
+    bus.cpu.x = value;
+    set_zero_negative(&mut bus.cpu, value);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn lxa(bus: &mut Bus, address_mode: AddressMode) {
+    // Unstable immediate LAX, A and X = (A | magic) & immediate
+    log_instruct("LXA", &address_mode, Some(bus));
+    let value = bus.read_operand(&address_mode);
+    let res = (bus.cpu.a | 0xEE) & value;
+    bus.cpu.a = res;
+    bus.cpu.x = res;
+    set_zero_negative(&mut bus.cpu, res);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn rla(bus: &mut Bus, address_mode: AddressMode) {
+    // ROL memory then AND
+    log_instruct("RLA", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = (value << 1) | cpu.get_carry();
+        cpu.set_carry(value & 0x80 != 0);
+        let acc = cpu.a & res;
+        cpu.a = acc;
+        set_zero_negative(cpu, acc);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn rra(bus: &mut Bus, address_mode: AddressMode) {
+    // ROR memory then ADC
+    log_instruct("RRA", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = (value >> 1) | (cpu.get_carry() << 7);
+        cpu.set_carry(value & 0x01 != 0);
+        add_to_accumulator(cpu, res);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn sax(bus: &mut Bus, address_mode: AddressMode) {
+    // Store A & X, flags are untouched
+    log_instruct("SAX", &address_mode, Some(bus));
+    bus.write_operand(&address_mode, bus.cpu.a & bus.cpu.x);
+    bus.increment_pc(&address_mode);
+}
+
+fn store_and_high(bus: &mut Bus, address_mode: &AddressMode, value: u8, index: u8) {
+    // SHA/SHX/SHY/TAS store value & (high byte of the base address + 1). When
+    // the index crosses a page the stored value also replaces the high byte
+    let (address, crossed) = bus.operand_address(address_mode, true);
+    let base = address.wrapping_sub(index as u16);
+    let res = value & ((base >> 8) as u8).wrapping_add(1);
+    let address = if crossed {
+        ((res as u16) << 8) | (address & 0x00FF)
+    } else {
+        address
+    };
+    bus.write(address, res);
+}
+
+pub fn sha(bus: &mut Bus, address_mode: AddressMode) {
+    // Store A & X & (H + 1)
+    log_instruct("SHA", &address_mode, Some(bus));
+    let (value, index) = (bus.cpu.a & bus.cpu.x, bus.cpu.y);
+    store_and_high(bus, &address_mode, value, index);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn shx(bus: &mut Bus, address_mode: AddressMode) {
+    // Store X & (H + 1)
+    log_instruct("SHX", &address_mode, Some(bus));
+    let (value, index) = (bus.cpu.x, bus.cpu.y);
+    store_and_high(bus, &address_mode, value, index);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn shy(bus: &mut Bus, address_mode: AddressMode) {
+    // Store Y & (H + 1)
+    log_instruct("SHY", &address_mode, Some(bus));
+    let (value, index) = (bus.cpu.y, bus.cpu.x);
+    store_and_high(bus, &address_mode, value, index);
+    bus.increment_pc(&address_mode);
+}
+
+pub fn slo(bus: &mut Bus, address_mode: AddressMode) {
+    // ASL memory then ORA
+    log_instruct("SLO", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value << 1;
+        cpu.set_carry(value & 0x80 != 0);
+        let acc = cpu.a | res;
+        cpu.a = acc;
+        set_zero_negative(cpu, acc);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn sre(bus: &mut Bus, address_mode: AddressMode) {
+    // LSR memory then EOR
+    log_instruct("SRE", &address_mode, Some(bus));
+    bus.modify_operand(&address_mode, |cpu, value| {
+        let res = value >> 1;
+        cpu.set_carry(value & 0x01 != 0);
+        let acc = cpu.a ^ res;
+        cpu.a = acc;
+        set_zero_negative(cpu, acc);
+        res
+    });
+    bus.increment_pc(&address_mode);
+}
+
+pub fn tas(bus: &mut Bus, address_mode: AddressMode) {
+    // S = A & X, then store S & (H + 1)
+    log_instruct("TAS", &address_mode, Some(bus));
+    let (value, index) = (bus.cpu.a & bus.cpu.x, bus.cpu.y);
+    bus.cpu.s = value;
+    store_and_high(bus, &address_mode, value, index);
     bus.increment_pc(&address_mode);
-    2 // TYA takes 2 cycles
 }
@@ -1,6 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
-
-use super::{enums::Registers, memory::Memory};
+use super::enums::Registers;
 
 pub mod instructions;
 pub mod opcode;
@@ -13,12 +11,11 @@ pub struct CPU {
     s: u8,
     p: u8,
     pc: u16,
-    pub delayed_interrupt_flag: Option<bool>,
-    memory: Rc<RefCell<Memory>>,
+    pub jammed: bool, // Set by KIL, cleared by reset
 }
 
 impl CPU {
-    pub fn new(memory: Rc<RefCell<Memory>>) -> Self {
+    pub fn new() -> Self {
         CPU {
             a: 1,
             x: 0,
@@ -26,8 +23,7 @@ impl CPU {
             s: 0,
             p: 0b1000,
             pc: 0xFFFC,
-            delayed_interrupt_flag: None,
-            memory,
+            jammed: false,
         }
     }
 
@@ -58,9 +54,6 @@ impl CPU {
     pub fn set_negative(&mut self, state: bool) {
         self.set_status(7, state);
     }
-    pub fn set_break(&mut self, state: bool) {
-        self.set_status(4, state);
-    }
 
     pub fn get_carry(&self) -> u8 {
         let flag = self.get_status(0);
@@ -144,50 +137,7 @@ impl CPU {
         self.s = 0xFD; // Stack starts at 0xFD
         self.pc = 0x0;
         self.p = 0b00000100; // Set unused bit, clear others
-        self.delayed_interrupt_flag = None;
+        self.jammed = false;
         println!("CPU reset.");
     }
-
-    pub fn stack_push(&mut self, value: u8) {
-        let new_sp = self.get(Registers::S).wrapping_sub(1);
-        self.set(Registers::S, new_sp);
-        self.memory.borrow_mut().write(0x100 + new_sp as u16, value);
-    }
-
-    pub fn stack_pull(&mut self) -> u8 {
-        let sp = self.get(Registers::S);
-        self.set(Registers::S, sp + 1);
-        self.memory.borrow().read(0x100 + sp as u16)
-    }
-
-    pub fn stack_push_word(&mut self, value: u16) {
-        let low_byte = (value & 0x00FF) as u8;
-        let high_byte = ((value & 0xFF00) >> 8) as u8;
-        self.stack_push(low_byte);
-        self.stack_push(high_byte);
-    }
-
-    pub fn stack_pull_word(&mut self) -> u16 {
-        let high_byte = self.stack_pull();
-        let low_byte = self.stack_pull();
-        ((high_byte as u16) << 8) + (low_byte as u16)
-    }
-
-    pub fn nmi(&mut self, nmi_vector: u16) -> u8 {
-        // TODO: Fix NMI handling
-        // this is not working correctly
-        println!("NMI triggered at PC: {:#04X}", self.pc);
-        self.stack_push_word(self.pc);
-
-        let mut p = self.p;
-        p &= !0x10;
-        p |= 0x20;
-        self.stack_push(p);
-
-        self.set_interrupt_disable(true);
-        println!("Reading NMI vector at 0xFFFA");
-        println!("NMI vector: {:#04X}", nmi_vector);
-        self.pc = nmi_vector;
-        return 7;
-    }
 }
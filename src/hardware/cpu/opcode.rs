@@ -5,766 +5,612 @@ use super::instructions::{self, AddressMode};
 pub struct Instruction {
     pub name: &'static str,
     pub address_mode: AddressMode,
-    pub execute: fn(&mut bus::Bus, address_mode: AddressMode) -> u8,
+    pub bytes: u8,
+    pub cycles: u8, // Base cycle count
+    // Reads take an extra cycle on a page cross and branches one when taken
+    // plus one for a page cross. Stores and read-modify-write opcodes always
+    // take the slow path and have it in the base count
+    pub page_penalty: bool,
+    // Runs the instruction cycle by cycle on the bus
+    pub execute: fn(&mut bus::Bus, address_mode: AddressMode),
 }
 
-pub fn get_instruction(opcode: u8) -> Option<Instruction> {
-    match opcode {
-        0x00 => Some(Instruction {
-            name: "BRK",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::brk,
-        }),
-        0x01 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::ora,
-        }),
-        0x05 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::ora,
-        }),
-        0x06 => Some(Instruction {
-            name: "ASL",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::asl,
-        }),
-        0x08 => Some(Instruction {
-            name: "PHP",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::php,
-        }),
-        0x09 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::ora,
-        }),
-        0x0A => Some(Instruction {
-            name: "ASL",
-            address_mode: AddressMode::Accumulator,
-            execute: instructions::asl,
-        }),
-        0x0D => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::ora,
-        }),
-        0x0E => Some(Instruction {
-            name: "ASL",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::asl,
-        }),
-        0x10 => Some(Instruction {
-            name: "BPL",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bpl,
-        }),
-        0x11 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::ora,
-        }),
-        0x15 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::ora,
-        }),
-        0x16 => Some(Instruction {
-            name: "ASL",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::asl,
-        }),
-        0x18 => Some(Instruction {
-            name: "CLC",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::clc,
-        }),
-        0x19 => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::ora,
-        }),
-        0x1D => Some(Instruction {
-            name: "ORA",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::ora,
-        }),
-        0x1E => Some(Instruction {
-            name: "ASL",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::asl,
-        }),
-        0x20 => Some(Instruction {
-            name: "JSR",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::jsr,
-        }),
-        0x21 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::and,
-        }),
-        0x24 => Some(Instruction {
-            name: "BIT",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::bit,
-        }),
-        0x25 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::and,
-        }),
-        0x26 => Some(Instruction {
-            name: "ROL",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::rol,
-        }),
-        0x28 => Some(Instruction {
-            name: "PLP",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::plp,
-        }),
-        0x29 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::and,
-        }),
-        0x2A => Some(Instruction {
-            name: "ROL",
-            address_mode: AddressMode::Accumulator,
-            execute: instructions::rol,
-        }),
-        0x2C => Some(Instruction {
-            name: "BIT",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::bit,
-        }),
-        0x2D => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::and,
-        }),
-        0x2E => Some(Instruction {
-            name: "ROL",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::rol,
-        }),
-        0x30 => Some(Instruction {
-            name: "BMI",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bmi,
-        }),
-        0x31 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::and,
-        }),
-        0x35 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::and,
-        }),
-        0x36 => Some(Instruction {
-            name: "ROL",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::rol,
-        }),
-        0x38 => Some(Instruction {
-            name: "SEC",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::sec,
-        }),
-        0x39 => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::and,
-        }),
-        0x3D => Some(Instruction {
-            name: "AND",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::and,
-        }),
-        0x3E => Some(Instruction {
-            name: "ROL",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::rol,
-        }),
-        0x40 => Some(Instruction {
-            name: "RTI",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::rti,
-        }),
-        0x41 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::eor,
-        }),
-        0x45 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::eor,
-        }),
-        0x46 => Some(Instruction {
-            name: "LSR",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::lsr,
-        }),
-        0x48 => Some(Instruction {
-            name: "PHA",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::pha,
-        }),
-        0x49 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::eor,
-        }),
-        0x4A => Some(Instruction {
-            name: "LSR",
-            address_mode: AddressMode::Accumulator,
-            execute: instructions::lsr,
-        }),
-        0x4C => Some(Instruction {
-            name: "JMP",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::jmp,
-        }),
-        0x4D => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::eor,
-        }),
-        0x4E => Some(Instruction {
-            name: "LSR",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::lsr,
-        }),
-        0x50 => Some(Instruction {
-            name: "BVC",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bvc,
-        }),
-        0x51 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::eor,
-        }),
-        0x55 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::eor,
-        }),
-        0x56 => Some(Instruction {
-            name: "LSR",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::lsr,
-        }),
-        0x58 => Some(Instruction {
-            name: "CLI",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::cli,
-        }),
-        0x59 => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::eor,
-        }),
-        0x5D => Some(Instruction {
-            name: "EOR",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::eor,
-        }),
-        0x5E => Some(Instruction {
-            name: "LSR",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::lsr,
-        }),
-        0x60 => Some(Instruction {
-            name: "RTS",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::rts,
-        }),
-        0x61 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::adc,
-        }),
-        0x65 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::adc,
-        }),
-        0x66 => Some(Instruction {
-            name: "ROR",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::ror,
-        }),
-        0x68 => Some(Instruction {
-            name: "PLA",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::pla,
-        }),
-        0x69 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::adc,
-        }),
-        0x6A => Some(Instruction {
-            name: "ROR",
-            address_mode: AddressMode::Accumulator,
-            execute: instructions::ror,
-        }),
-        0x6C => Some(Instruction {
-            name: "JMP",
-            address_mode: AddressMode::Indirect,
-            execute: instructions::jmp,
-        }),
-        0x6D => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::adc,
-        }),
-        0x6E => Some(Instruction {
-            name: "ROR",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::ror,
-        }),
-        0x70 => Some(Instruction {
-            name: "BVS",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bvs,
-        }),
-        0x71 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::adc,
-        }),
-        0x75 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::adc,
-        }),
-        0x76 => Some(Instruction {
-            name: "ROR",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::ror,
-        }),
-        0x78 => Some(Instruction {
-            name: "SEI",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::sei,
-        }),
-        0x79 => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::adc,
-        }),
-        0x7D => Some(Instruction {
-            name: "ADC",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::adc,
-        }),
-        0x7E => Some(Instruction {
-            name: "ROR",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::ror,
-        }),
-        0x81 => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::sta,
-        }),
-        0x84 => Some(Instruction {
-            name: "STY",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::sty,
-        }),
-        0x85 => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::sta,
-        }),
-        0x86 => Some(Instruction {
-            name: "STX",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::stx,
-        }),
-        0x88 => Some(Instruction {
-            name: "DEY",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::dey,
-        }),
-        0x8A => Some(Instruction {
-            name: "TXA",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::txa,
-        }),
-        0x8C => Some(Instruction {
-            name: "STY",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::sty,
-        }),
-        0x8D => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::sta,
-        }),
-        0x8E => Some(Instruction {
-            name: "STX",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::stx,
-        }),
-        0x90 => Some(Instruction {
-            name: "BCC",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bcc,
-        }),
-        0x91 => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::sta,
-        }),
-        0x94 => Some(Instruction {
-            name: "STY",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::sty,
-        }),
-        0x95 => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::sta,
-        }),
-        0x96 => Some(Instruction {
-            name: "STX",
-            address_mode: AddressMode::ZeroPageY,
-            execute: instructions::stx,
-        }),
-        0x98 => Some(Instruction {
-            name: "TYA",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::tya,
-        }),
-        0x99 => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::sta,
-        }),
-        0x9A => Some(Instruction {
-            name: "TXS",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::txs,
-        }),
-        0x9D => Some(Instruction {
-            name: "STA",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::sta,
-        }),
-        0xA0 => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::ldy,
-        }),
-        0xA1 => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::ldy,
-        }),
-        0xA2 => Some(Instruction {
-            name: "LDX",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::ldx,
-        }),
-        0xA4 => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::ldy,
-        }),
-        0xA5 => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::lda,
-        }),
-        0xA6 => Some(Instruction {
-            name: "LDX",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::ldx,
-        }),
-        0xA8 => Some(Instruction {
-            name: "TAY",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::tay,
-        }),
-        0xA9 => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::lda,
-        }),
-        0xAA => Some(Instruction {
-            name: "TAX",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::tax,
-        }),
-        0xAC => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::ldy,
-        }),
-        0xAD => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::lda,
-        }),
-        0xAE => Some(Instruction {
-            name: "LDX",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::ldx,
-        }),
-        0xB0 => Some(Instruction {
-            name: "BCS",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bcs,
-        }),
-        0xB1 => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::lda,
-        }),
-        0xB4 => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::ldy,
-        }),
-        0xB5 => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::lda,
-        }),
-        0xB6 => Some(Instruction {
-            name: "LDX",
-            address_mode: AddressMode::ZeroPageY,
-            execute: instructions::ldx,
-        }),
-        0xB8 => Some(Instruction {
-            name: "CLV",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::clv,
-        }),
-        0xB9 => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::lda,
-        }),
-        0xBA => Some(Instruction {
-            name: "TSX",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::tsx,
-        }),
-        0xBC => Some(Instruction {
-            name: "LDY",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::ldy,
-        }),
-        0xBD => Some(Instruction {
-            name: "LDA",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::lda,
-        }),
-        0xBE => Some(Instruction {
-            name: "LDX",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::ldx,
-        }),
-        0xC0 => Some(Instruction {
-            name: "CPY",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::cpy,
-        }),
-        0xC1 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::cmp,
-        }),
-        0xC4 => Some(Instruction {
-            name: "CPY",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::cpy,
-        }),
-        0xC5 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::cmp,
-        }),
-        0xC6 => Some(Instruction {
-            name: "DEC",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::dec,
-        }),
-        0xC8 => Some(Instruction {
-            name: "INY",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::iny,
-        }),
-        0xC9 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::cmp,
-        }),
-        0xCA => Some(Instruction {
-            name: "DEX",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::dex,
-        }),
-        0xCC => Some(Instruction {
-            name: "CPY",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::cpy,
-        }),
-        0xCD => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::cmp,
-        }),
-        0xCE => Some(Instruction {
-            name: "DEC",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::dec,
-        }),
-        0xD0 => Some(Instruction {
-            name: "BNE",
-            address_mode: AddressMode::Relative,
-            execute: instructions::bne,
-        }),
-        0xD1 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::cmp,
-        }),
-        0xD5 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::cmp,
-        }),
-        0xD6 => Some(Instruction {
-            name: "DEC",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::dec,
-        }),
-        0xD8 => Some(Instruction {
-            name: "CLD",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::cld,
-        }),
-        0xD9 => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::cmp,
-        }),
-        0xDD => Some(Instruction {
-            name: "CMP",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::cmp,
-        }),
-        0xDE => Some(Instruction {
-            name: "DEC",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::dec,
-        }),
-        0xE0 => Some(Instruction {
-            name: "CPX",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::cpx,
-        }),
-        0xE1 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::IndirectX,
-            execute: instructions::sbc,
-        }),
-        0xE4 => Some(Instruction {
-            name: "CPX",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::cpx,
-        }),
-        0xE5 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::sbc,
-        }),
-        0xE6 => Some(Instruction {
-            name: "INC",
-            address_mode: AddressMode::ZeroPage,
-            execute: instructions::inc,
-        }),
-        0xE8 => Some(Instruction {
-            name: "INX",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::inx,
-        }),
-        0xE9 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::Immediate,
-            execute: instructions::sbc,
-        }),
-        0xEA => Some(Instruction {
-            name: "NOP",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::nop,
-        }),
-        0xEC => Some(Instruction {
-            name: "CPX",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::cpx,
-        }),
-        0xED => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::sbc,
-        }),
-        0xEE => Some(Instruction {
-            name: "INC",
-            address_mode: AddressMode::Absolute,
-            execute: instructions::inc,
-        }),
-        0xF0 => Some(Instruction {
-            name: "BEQ",
-            address_mode: AddressMode::Relative,
-            execute: instructions::beq,
-        }),
-        0xF1 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::IndirectY,
-            execute: instructions::sbc,
-        }),
-        0xF5 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::sbc,
-        }),
-        0xF6 => Some(Instruction {
-            name: "INC",
-            address_mode: AddressMode::ZeroPageX,
-            execute: instructions::inc,
-        }),
-        0xF8 => Some(Instruction {
-            name: "SED",
-            address_mode: AddressMode::Implicit,
-            execute: instructions::sed,
-        }),
-        0xF9 => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::AbsoluteY,
-            execute: instructions::sbc,
-        }),
-        0xFD => Some(Instruction {
-            name: "SBC",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::sbc,
-        }),
-        0xFE => Some(Instruction {
-            name: "INC",
-            address_mode: AddressMode::AbsoluteX,
-            execute: instructions::inc,
-        }),
-        _ => None, // Return None for unknown opcodes
+impl Instruction {
+    pub fn max_cycles(&self) -> u8 {
+        if self.page_penalty {
+            self.cycles + 2
+        } else {
+            self.cycles
+        }
+    }
+}
+
+const fn op(
+    name: &'static str,
+    address_mode: AddressMode,
+    cycles: u8,
+    page_penalty: bool,
+    execute: fn(&mut bus::Bus, address_mode: AddressMode),
+) -> Instruction {
+    Instruction {
+        name,
+        address_mode,
+        bytes: address_mode.instruction_len(),
+        cycles,
+        page_penalty,
+        execute,
+    }
+}
+
+// Every opcode, the unofficial ones included
+pub static INSTRUCTIONS: [Instruction; 256] = [
+    op("BRK", AddressMode::Implicit, 7, false, instructions::brk), // 0x00
+    op("ORA", AddressMode::IndirectX, 6, false, instructions::ora), // 0x01
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x02
+    op("SLO", AddressMode::IndirectX, 8, false, instructions::slo), // 0x03
+    op("NOP", AddressMode::ZeroPage, 3, false, instructions::nop), // 0x04
+    op("ORA", AddressMode::ZeroPage, 3, false, instructions::ora), // 0x05
+    op("ASL", AddressMode::ZeroPage, 5, false, instructions::asl), // 0x06
+    op("SLO", AddressMode::ZeroPage, 5, false, instructions::slo), // 0x07
+    op("PHP", AddressMode::Implicit, 3, false, instructions::php), // 0x08
+    op("ORA", AddressMode::Immediate, 2, false, instructions::ora), // 0x09
+    op("ASL", AddressMode::Accumulator, 2, false, instructions::asl), // 0x0A
+    op("ANC", AddressMode::Immediate, 2, false, instructions::anc), // 0x0B
+    op("NOP", AddressMode::Absolute, 4, false, instructions::nop), // 0x0C
+    op("ORA", AddressMode::Absolute, 4, false, instructions::ora), // 0x0D
+    op("ASL", AddressMode::Absolute, 6, false, instructions::asl), // 0x0E
+    op("SLO", AddressMode::Absolute, 6, false, instructions::slo), // 0x0F
+    op("BPL", AddressMode::Relative, 2, true, instructions::bpl),  // 0x10
+    op("ORA", AddressMode::IndirectY, 5, true, instructions::ora), // 0x11
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x12
+    op("SLO", AddressMode::IndirectY, 8, false, instructions::slo), // 0x13
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0x14
+    op("ORA", AddressMode::ZeroPageX, 4, false, instructions::ora), // 0x15
+    op("ASL", AddressMode::ZeroPageX, 6, false, instructions::asl), // 0x16
+    op("SLO", AddressMode::ZeroPageX, 6, false, instructions::slo), // 0x17
+    op("CLC", AddressMode::Implicit, 2, false, instructions::clc), // 0x18
+    op("ORA", AddressMode::AbsoluteY, 4, true, instructions::ora), // 0x19
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0x1A
+    op("SLO", AddressMode::AbsoluteY, 7, false, instructions::slo), // 0x1B
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0x1C
+    op("ORA", AddressMode::AbsoluteX, 4, true, instructions::ora), // 0x1D
+    op("ASL", AddressMode::AbsoluteX, 7, false, instructions::asl), // 0x1E
+    op("SLO", AddressMode::AbsoluteX, 7, false, instructions::slo), // 0x1F
+    op("JSR", AddressMode::Absolute, 6, false, instructions::jsr), // 0x20
+    op("AND", AddressMode::IndirectX, 6, false, instructions::and), // 0x21
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x22
+    op("RLA", AddressMode::IndirectX, 8, false, instructions::rla), // 0x23
+    op("BIT", AddressMode::ZeroPage, 3, false, instructions::bit), // 0x24
+    op("AND", AddressMode::ZeroPage, 3, false, instructions::and), // 0x25
+    op("ROL", AddressMode::ZeroPage, 5, false, instructions::rol), // 0x26
+    op("RLA", AddressMode::ZeroPage, 5, false, instructions::rla), // 0x27
+    op("PLP", AddressMode::Implicit, 4, false, instructions::plp), // 0x28
+    op("AND", AddressMode::Immediate, 2, false, instructions::and), // 0x29
+    op("ROL", AddressMode::Accumulator, 2, false, instructions::rol), // 0x2A
+    op("ANC", AddressMode::Immediate, 2, false, instructions::anc), // 0x2B
+    op("BIT", AddressMode::Absolute, 4, false, instructions::bit), // 0x2C
+    op("AND", AddressMode::Absolute, 4, false, instructions::and), // 0x2D
+    op("ROL", AddressMode::Absolute, 6, false, instructions::rol), // 0x2E
+    op("RLA", AddressMode::Absolute, 6, false, instructions::rla), // 0x2F
+    op("BMI", AddressMode::Relative, 2, true, instructions::bmi),  // 0x30
+    op("AND", AddressMode::IndirectY, 5, true, instructions::and), // 0x31
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x32
+    op("RLA", AddressMode::IndirectY, 8, false, instructions::rla), // 0x33
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0x34
+    op("AND", AddressMode::ZeroPageX, 4, false, instructions::and), // 0x35
+    op("ROL", AddressMode::ZeroPageX, 6, false, instructions::rol), // 0x36
+    op("RLA", AddressMode::ZeroPageX, 6, false, instructions::rla), // 0x37
+    op("SEC", AddressMode::Implicit, 2, false, instructions::sec), // 0x38
+    op("AND", AddressMode::AbsoluteY, 4, true, instructions::and), // 0x39
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0x3A
+    op("RLA", AddressMode::AbsoluteY, 7, false, instructions::rla), // 0x3B
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0x3C
+    op("AND", AddressMode::AbsoluteX, 4, true, instructions::and), // 0x3D
+    op("ROL", AddressMode::AbsoluteX, 7, false, instructions::rol), // 0x3E
+    op("RLA", AddressMode::AbsoluteX, 7, false, instructions::rla), // 0x3F
+    op("RTI", AddressMode::Implicit, 6, false, instructions::rti), // 0x40
+    op("EOR", AddressMode::IndirectX, 6, false, instructions::eor), // 0x41
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x42
+    op("SRE", AddressMode::IndirectX, 8, false, instructions::sre), // 0x43
+    op("NOP", AddressMode::ZeroPage, 3, false, instructions::nop), // 0x44
+    op("EOR", AddressMode::ZeroPage, 3, false, instructions::eor), // 0x45
+    op("LSR", AddressMode::ZeroPage, 5, false, instructions::lsr), // 0x46
+    op("SRE", AddressMode::ZeroPage, 5, false, instructions::sre), // 0x47
+    op("PHA", AddressMode::Implicit, 3, false, instructions::pha), // 0x48
+    op("EOR", AddressMode::Immediate, 2, false, instructions::eor), // 0x49
+    op("LSR", AddressMode::Accumulator, 2, false, instructions::lsr), // 0x4A
+    op("ALR", AddressMode::Immediate, 2, false, instructions::alr), // 0x4B
+    op("JMP", AddressMode::Absolute, 3, false, instructions::jmp), // 0x4C
+    op("EOR", AddressMode::Absolute, 4, false, instructions::eor), // 0x4D
+    op("LSR", AddressMode::Absolute, 6, false, instructions::lsr), // 0x4E
+    op("SRE", AddressMode::Absolute, 6, false, instructions::sre), // 0x4F
+    op("BVC", AddressMode::Relative, 2, true, instructions::bvc),  // 0x50
+    op("EOR", AddressMode::IndirectY, 5, true, instructions::eor), // 0x51
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x52
+    op("SRE", AddressMode::IndirectY, 8, false, instructions::sre), // 0x53
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0x54
+    op("EOR", AddressMode::ZeroPageX, 4, false, instructions::eor), // 0x55
+    op("LSR", AddressMode::ZeroPageX, 6, false, instructions::lsr), // 0x56
+    op("SRE", AddressMode::ZeroPageX, 6, false, instructions::sre), // 0x57
+    op("CLI", AddressMode::Implicit, 2, false, instructions::cli), // 0x58
+    op("EOR", AddressMode::AbsoluteY, 4, true, instructions::eor), // 0x59
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0x5A
+    op("SRE", AddressMode::AbsoluteY, 7, false, instructions::sre), // 0x5B
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0x5C
+    op("EOR", AddressMode::AbsoluteX, 4, true, instructions::eor), // 0x5D
+    op("LSR", AddressMode::AbsoluteX, 7, false, instructions::lsr), // 0x5E
+    op("SRE", AddressMode::AbsoluteX, 7, false, instructions::sre), // 0x5F
+    op("RTS", AddressMode::Implicit, 6, false, instructions::rts), // 0x60
+    op("ADC", AddressMode::IndirectX, 6, false, instructions::adc), // 0x61
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x62
+    op("RRA", AddressMode::IndirectX, 8, false, instructions::rra), // 0x63
+    op("NOP", AddressMode::ZeroPage, 3, false, instructions::nop), // 0x64
+    op("ADC", AddressMode::ZeroPage, 3, false, instructions::adc), // 0x65
+    op("ROR", AddressMode::ZeroPage, 5, false, instructions::ror), // 0x66
+    op("RRA", AddressMode::ZeroPage, 5, false, instructions::rra), // 0x67
+    op("PLA", AddressMode::Implicit, 4, false, instructions::pla), // 0x68
+    op("ADC", AddressMode::Immediate, 2, false, instructions::adc), // 0x69
+    op("ROR", AddressMode::Accumulator, 2, false, instructions::ror), // 0x6A
+    op("ARR", AddressMode::Immediate, 2, false, instructions::arr), // 0x6B
+    op("JMP", AddressMode::Indirect, 5, false, instructions::jmp), // 0x6C
+    op("ADC", AddressMode::Absolute, 4, false, instructions::adc), // 0x6D
+    op("ROR", AddressMode::Absolute, 6, false, instructions::ror), // 0x6E
+    op("RRA", AddressMode::Absolute, 6, false, instructions::rra), // 0x6F
+    op("BVS", AddressMode::Relative, 2, true, instructions::bvs),  // 0x70
+    op("ADC", AddressMode::IndirectY, 5, true, instructions::adc), // 0x71
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x72
+    op("RRA", AddressMode::IndirectY, 8, false, instructions::rra), // 0x73
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0x74
+    op("ADC", AddressMode::ZeroPageX, 4, false, instructions::adc), // 0x75
+    op("ROR", AddressMode::ZeroPageX, 6, false, instructions::ror), // 0x76
+    op("RRA", AddressMode::ZeroPageX, 6, false, instructions::rra), // 0x77
+    op("SEI", AddressMode::Implicit, 2, false, instructions::sei), // 0x78
+    op("ADC", AddressMode::AbsoluteY, 4, true, instructions::adc), // 0x79
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0x7A
+    op("RRA", AddressMode::AbsoluteY, 7, false, instructions::rra), // 0x7B
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0x7C
+    op("ADC", AddressMode::AbsoluteX, 4, true, instructions::adc), // 0x7D
+    op("ROR", AddressMode::AbsoluteX, 7, false, instructions::ror), // 0x7E
+    op("RRA", AddressMode::AbsoluteX, 7, false, instructions::rra), // 0x7F
+    op("NOP", AddressMode::Immediate, 2, false, instructions::nop), // 0x80
+    op("STA", AddressMode::IndirectX, 6, false, instructions::sta), // 0x81
+    op("NOP", AddressMode::Immediate, 2, false, instructions::nop), // 0x82
+    op("SAX", AddressMode::IndirectX, 6, false, instructions::sax), // 0x83
+    op("STY", AddressMode::ZeroPage, 3, false, instructions::sty), // 0x84
+    op("STA", AddressMode::ZeroPage, 3, false, instructions::sta), // 0x85
+    op("STX", AddressMode::ZeroPage, 3, false, instructions::stx), // 0x86
+    op("SAX", AddressMode::ZeroPage, 3, false, instructions::sax), // 0x87
+    op("DEY", AddressMode::Implicit, 2, false, instructions::dey), // 0x88
+    op("NOP", AddressMode::Immediate, 2, false, instructions::nop), // 0x89
+    op("TXA", AddressMode::Implicit, 2, false, instructions::txa), // 0x8A
+    op("ANE", AddressMode::Immediate, 2, false, instructions::ane), // 0x8B
+    op("STY", AddressMode::Absolute, 4, false, instructions::sty), // 0x8C
+    op("STA", AddressMode::Absolute, 4, false, instructions::sta), // 0x8D
+    op("STX", AddressMode::Absolute, 4, false, instructions::stx), // 0x8E
+    op("SAX", AddressMode::Absolute, 4, false, instructions::sax), // 0x8F
+    op("BCC", AddressMode::Relative, 2, true, instructions::bcc),  // 0x90
+    op("STA", AddressMode::IndirectY, 6, false, instructions::sta), // 0x91
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0x92
+    op("SHA", AddressMode::IndirectY, 6, false, instructions::sha), // 0x93
+    op("STY", AddressMode::ZeroPageX, 4, false, instructions::sty), // 0x94
+    op("STA", AddressMode::ZeroPageX, 4, false, instructions::sta), // 0x95
+    op("STX", AddressMode::ZeroPageY, 4, false, instructions::stx), // 0x96
+    op("SAX", AddressMode::ZeroPageY, 4, false, instructions::sax), // 0x97
+    op("TYA", AddressMode::Implicit, 2, false, instructions::tya), // 0x98
+    op("STA", AddressMode::AbsoluteY, 5, false, instructions::sta), // 0x99
+    op("TXS", AddressMode::Implicit, 2, false, instructions::txs), // 0x9A
+    op("TAS", AddressMode::AbsoluteY, 5, false, instructions::tas), // 0x9B
+    op("SHY", AddressMode::AbsoluteX, 5, false, instructions::shy), // 0x9C
+    op("STA", AddressMode::AbsoluteX, 5, false, instructions::sta), // 0x9D
+    op("SHX", AddressMode::AbsoluteY, 5, false, instructions::shx), // 0x9E
+    op("SHA", AddressMode::AbsoluteY, 5, false, instructions::sha), // 0x9F
+    op("LDY", AddressMode::Immediate, 2, false, instructions::ldy), // 0xA0
+    op("LDA", AddressMode::IndirectX, 6, false, instructions::lda), // 0xA1
+    op("LDX", AddressMode::Immediate, 2, false, instructions::ldx), // 0xA2
+    op("LAX", AddressMode::IndirectX, 6, false, instructions::lax), // 0xA3
+    op("LDY", AddressMode::ZeroPage, 3, false, instructions::ldy), // 0xA4
+    op("LDA", AddressMode::ZeroPage, 3, false, instructions::lda), // 0xA5
+    op("LDX", AddressMode::ZeroPage, 3, false, instructions::ldx), // 0xA6
+    op("LAX", AddressMode::ZeroPage, 3, false, instructions::lax), // 0xA7
+    op("TAY", AddressMode::Implicit, 2, false, instructions::tay), // 0xA8
+    op("LDA", AddressMode::Immediate, 2, false, instructions::lda), // 0xA9
+    op("TAX", AddressMode::Implicit, 2, false, instructions::tax), // 0xAA
+    op("LXA", AddressMode::Immediate, 2, false, instructions::lxa), // 0xAB
+    op("LDY", AddressMode::Absolute, 4, false, instructions::ldy), // 0xAC
+    op("LDA", AddressMode::Absolute, 4, false, instructions::lda), // 0xAD
+    op("LDX", AddressMode::Absolute, 4, false, instructions::ldx), // 0xAE
+    op("LAX", AddressMode::Absolute, 4, false, instructions::lax), // 0xAF
+    op("BCS", AddressMode::Relative, 2, true, instructions::bcs),  // 0xB0
+    op("LDA", AddressMode::IndirectY, 5, true, instructions::lda), // 0xB1
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0xB2
+    op("LAX", AddressMode::IndirectY, 5, true, instructions::lax), // 0xB3
+    op("LDY", AddressMode::ZeroPageX, 4, false, instructions::ldy), // 0xB4
+    op("LDA", AddressMode::ZeroPageX, 4, false, instructions::lda), // 0xB5
+    op("LDX", AddressMode::ZeroPageY, 4, false, instructions::ldx), // 0xB6
+    op("LAX", AddressMode::ZeroPageY, 4, false, instructions::lax), // 0xB7
+    op("CLV", AddressMode::Implicit, 2, false, instructions::clv), // 0xB8
+    op("LDA", AddressMode::AbsoluteY, 4, true, instructions::lda), // 0xB9
+    op("TSX", AddressMode::Implicit, 2, false, instructions::tsx), // 0xBA
+    op("LAS", AddressMode::AbsoluteY, 4, true, instructions::las), // 0xBB
+    op("LDY", AddressMode::AbsoluteX, 4, true, instructions::ldy), // 0xBC
+    op("LDA", AddressMode::AbsoluteX, 4, true, instructions::lda), // 0xBD
+    op("LDX", AddressMode::AbsoluteY, 4, true, instructions::ldx), // 0xBE
+    op("LAX", AddressMode::AbsoluteY, 4, true, instructions::lax), // 0xBF
+    op("CPY", AddressMode::Immediate, 2, false, instructions::cpy), // 0xC0
+    op("CMP", AddressMode::IndirectX, 6, false, instructions::cmp), // 0xC1
+    op("NOP", AddressMode::Immediate, 2, false, instructions::nop), // 0xC2
+    op("DCP", AddressMode::IndirectX, 8, false, instructions::dcp), // 0xC3
+    op("CPY", AddressMode::ZeroPage, 3, false, instructions::cpy), // 0xC4
+    op("CMP", AddressMode::ZeroPage, 3, false, instructions::cmp), // 0xC5
+    op("DEC", AddressMode::ZeroPage, 5, false, instructions::dec), // 0xC6
+    op("DCP", AddressMode::ZeroPage, 5, false, instructions::dcp), // 0xC7
+    op("INY", AddressMode::Implicit, 2, false, instructions::iny), // 0xC8
+    op("CMP", AddressMode::Immediate, 2, false, instructions::cmp), // 0xC9
+    op("DEX", AddressMode::Implicit, 2, false, instructions::dex), // 0xCA
+    op("AXS", AddressMode::Immediate, 2, false, instructions::axs), // 0xCB
+    op("CPY", AddressMode::Absolute, 4, false, instructions::cpy), // 0xCC
+    op("CMP", AddressMode::Absolute, 4, false, instructions::cmp), // 0xCD
+    op("DEC", AddressMode::Absolute, 6, false, instructions::dec), // 0xCE
+    op("DCP", AddressMode::Absolute, 6, false, instructions::dcp), // 0xCF
+    op("BNE", AddressMode::Relative, 2, true, instructions::bne),  // 0xD0
+    op("CMP", AddressMode::IndirectY, 5, true, instructions::cmp), // 0xD1
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0xD2
+    op("DCP", AddressMode::IndirectY, 8, false, instructions::dcp), // 0xD3
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0xD4
+    op("CMP", AddressMode::ZeroPageX, 4, false, instructions::cmp), // 0xD5
+    op("DEC", AddressMode::ZeroPageX, 6, false, instructions::dec), // 0xD6
+    op("DCP", AddressMode::ZeroPageX, 6, false, instructions::dcp), // 0xD7
+    op("CLD", AddressMode::Implicit, 2, false, instructions::cld), // 0xD8
+    op("CMP", AddressMode::AbsoluteY, 4, true, instructions::cmp), // 0xD9
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0xDA
+    op("DCP", AddressMode::AbsoluteY, 7, false, instructions::dcp), // 0xDB
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0xDC
+    op("CMP", AddressMode::AbsoluteX, 4, true, instructions::cmp), // 0xDD
+    op("DEC", AddressMode::AbsoluteX, 7, false, instructions::dec), // 0xDE
+    op("DCP", AddressMode::AbsoluteX, 7, false, instructions::dcp), // 0xDF
+    op("CPX", AddressMode::Immediate, 2, false, instructions::cpx), // 0xE0
+    op("SBC", AddressMode::IndirectX, 6, false, instructions::sbc), // 0xE1
+    op("NOP", AddressMode::Immediate, 2, false, instructions::nop), // 0xE2
+    op("ISC", AddressMode::IndirectX, 8, false, instructions::isc), // 0xE3
+    op("CPX", AddressMode::ZeroPage, 3, false, instructions::cpx), // 0xE4
+    op("SBC", AddressMode::ZeroPage, 3, false, instructions::sbc), // 0xE5
+    op("INC", AddressMode::ZeroPage, 5, false, instructions::inc), // 0xE6
+    op("ISC", AddressMode::ZeroPage, 5, false, instructions::isc), // 0xE7
+    op("INX", AddressMode::Implicit, 2, false, instructions::inx), // 0xE8
+    op("SBC", AddressMode::Immediate, 2, false, instructions::sbc), // 0xE9
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0xEA
+    op("SBC", AddressMode::Immediate, 2, false, instructions::sbc), // 0xEB
+    op("CPX", AddressMode::Absolute, 4, false, instructions::cpx), // 0xEC
+    op("SBC", AddressMode::Absolute, 4, false, instructions::sbc), // 0xED
+    op("INC", AddressMode::Absolute, 6, false, instructions::inc), // 0xEE
+    op("ISC", AddressMode::Absolute, 6, false, instructions::isc), // 0xEF
+    op("BEQ", AddressMode::Relative, 2, true, instructions::beq),  // 0xF0
+    op("SBC", AddressMode::IndirectY, 5, true, instructions::sbc), // 0xF1
+    op("KIL", AddressMode::Implicit, 2, false, instructions::kil), // 0xF2
+    op("ISC", AddressMode::IndirectY, 8, false, instructions::isc), // 0xF3
+    op("NOP", AddressMode::ZeroPageX, 4, false, instructions::nop), // 0xF4
+    op("SBC", AddressMode::ZeroPageX, 4, false, instructions::sbc), // 0xF5
+    op("INC", AddressMode::ZeroPageX, 6, false, instructions::inc), // 0xF6
+    op("ISC", AddressMode::ZeroPageX, 6, false, instructions::isc), // 0xF7
+    op("SED", AddressMode::Implicit, 2, false, instructions::sed), // 0xF8
+    op("SBC", AddressMode::AbsoluteY, 4, true, instructions::sbc), // 0xF9
+    op("NOP", AddressMode::Implicit, 2, false, instructions::nop), // 0xFA
+    op("ISC", AddressMode::AbsoluteY, 7, false, instructions::isc), // 0xFB
+    op("NOP", AddressMode::AbsoluteX, 4, true, instructions::nop), // 0xFC
+    op("SBC", AddressMode::AbsoluteX, 4, true, instructions::sbc), // 0xFD
+    op("INC", AddressMode::AbsoluteX, 7, false, instructions::inc), // 0xFE
+    op("ISC", AddressMode::AbsoluteX, 7, false, instructions::isc), // 0xFF
+];
+
+pub fn get_instruction(opcode: u8) -> &'static Instruction {
+    &INSTRUCTIONS[opcode as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The 6502 opcode matrix in opcode order, `*` marks the page-cross
+    // (or taken branch) penalty
+    const REFERENCE: [&str; 256] = [
+        "BRK impl 7",
+        "ORA izx 6",
+        "KIL impl 2",
+        "SLO izx 8",
+        "NOP zp 3",
+        "ORA zp 3",
+        "ASL zp 5",
+        "SLO zp 5",
+        "PHP impl 3",
+        "ORA imm 2",
+        "ASL acc 2",
+        "ANC imm 2",
+        "NOP abs 4",
+        "ORA abs 4",
+        "ASL abs 6",
+        "SLO abs 6",
+        "BPL rel 2*",
+        "ORA izy 5*",
+        "KIL impl 2",
+        "SLO izy 8",
+        "NOP zpx 4",
+        "ORA zpx 4",
+        "ASL zpx 6",
+        "SLO zpx 6",
+        "CLC impl 2",
+        "ORA aby 4*",
+        "NOP impl 2",
+        "SLO aby 7",
+        "NOP abx 4*",
+        "ORA abx 4*",
+        "ASL abx 7",
+        "SLO abx 7",
+        "JSR abs 6",
+        "AND izx 6",
+        "KIL impl 2",
+        "RLA izx 8",
+        "BIT zp 3",
+        "AND zp 3",
+        "ROL zp 5",
+        "RLA zp 5",
+        "PLP impl 4",
+        "AND imm 2",
+        "ROL acc 2",
+        "ANC imm 2",
+        "BIT abs 4",
+        "AND abs 4",
+        "ROL abs 6",
+        "RLA abs 6",
+        "BMI rel 2*",
+        "AND izy 5*",
+        "KIL impl 2",
+        "RLA izy 8",
+        "NOP zpx 4",
+        "AND zpx 4",
+        "ROL zpx 6",
+        "RLA zpx 6",
+        "SEC impl 2",
+        "AND aby 4*",
+        "NOP impl 2",
+        "RLA aby 7",
+        "NOP abx 4*",
+        "AND abx 4*",
+        "ROL abx 7",
+        "RLA abx 7",
+        "RTI impl 6",
+        "EOR izx 6",
+        "KIL impl 2",
+        "SRE izx 8",
+        "NOP zp 3",
+        "EOR zp 3",
+        "LSR zp 5",
+        "SRE zp 5",
+        "PHA impl 3",
+        "EOR imm 2",
+        "LSR acc 2",
+        "ALR imm 2",
+        "JMP abs 3",
+        "EOR abs 4",
+        "LSR abs 6",
+        "SRE abs 6",
+        "BVC rel 2*",
+        "EOR izy 5*",
+        "KIL impl 2",
+        "SRE izy 8",
+        "NOP zpx 4",
+        "EOR zpx 4",
+        "LSR zpx 6",
+        "SRE zpx 6",
+        "CLI impl 2",
+        "EOR aby 4*",
+        "NOP impl 2",
+        "SRE aby 7",
+        "NOP abx 4*",
+        "EOR abx 4*",
+        "LSR abx 7",
+        "SRE abx 7",
+        "RTS impl 6",
+        "ADC izx 6",
+        "KIL impl 2",
+        "RRA izx 8",
+        "NOP zp 3",
+        "ADC zp 3",
+        "ROR zp 5",
+        "RRA zp 5",
+        "PLA impl 4",
+        "ADC imm 2",
+        "ROR acc 2",
+        "ARR imm 2",
+        "JMP ind 5",
+        "ADC abs 4",
+        "ROR abs 6",
+        "RRA abs 6",
+        "BVS rel 2*",
+        "ADC izy 5*",
+        "KIL impl 2",
+        "RRA izy 8",
+        "NOP zpx 4",
+        "ADC zpx 4",
+        "ROR zpx 6",
+        "RRA zpx 6",
+        "SEI impl 2",
+        "ADC aby 4*",
+        "NOP impl 2",
+        "RRA aby 7",
+        "NOP abx 4*",
+        "ADC abx 4*",
+        "ROR abx 7",
+        "RRA abx 7",
+        "NOP imm 2",
+        "STA izx 6",
+        "NOP imm 2",
+        "SAX izx 6",
+        "STY zp 3",
+        "STA zp 3",
+        "STX zp 3",
+        "SAX zp 3",
+        "DEY impl 2",
+        "NOP imm 2",
+        "TXA impl 2",
+        "ANE imm 2",
+        "STY abs 4",
+        "STA abs 4",
+        "STX abs 4",
+        "SAX abs 4",
+        "BCC rel 2*",
+        "STA izy 6",
+        "KIL impl 2",
+        "SHA izy 6",
+        "STY zpx 4",
+        "STA zpx 4",
+        "STX zpy 4",
+        "SAX zpy 4",
+        "TYA impl 2",
+        "STA aby 5",
+        "TXS impl 2",
+        "TAS aby 5",
+        "SHY abx 5",
+        "STA abx 5",
+        "SHX aby 5",
+        "SHA aby 5",
+        "LDY imm 2",
+        "LDA izx 6",
+        "LDX imm 2",
+        "LAX izx 6",
+        "LDY zp 3",
+        "LDA zp 3",
+        "LDX zp 3",
+        "LAX zp 3",
+        "TAY impl 2",
+        "LDA imm 2",
+        "TAX impl 2",
+        "LXA imm 2",
+        "LDY abs 4",
+        "LDA abs 4",
+        "LDX abs 4",
+        "LAX abs 4",
+        "BCS rel 2*",
+        "LDA izy 5*",
+        "KIL impl 2",
+        "LAX izy 5*",
+        "LDY zpx 4",
+        "LDA zpx 4",
+        "LDX zpy 4",
+        "LAX zpy 4",
+        "CLV impl 2",
+        "LDA aby 4*",
+        "TSX impl 2",
+        "LAS aby 4*",
+        "LDY abx 4*",
+        "LDA abx 4*",
+        "LDX aby 4*",
+        "LAX aby 4*",
+        "CPY imm 2",
+        "CMP izx 6",
+        "NOP imm 2",
+        "DCP izx 8",
+        "CPY zp 3",
+        "CMP zp 3",
+        "DEC zp 5",
+        "DCP zp 5",
+        "INY impl 2",
+        "CMP imm 2",
+        "DEX impl 2",
+        "AXS imm 2",
+        "CPY abs 4",
+        "CMP abs 4",
+        "DEC abs 6",
+        "DCP abs 6",
+        "BNE rel 2*",
+        "CMP izy 5*",
+        "KIL impl 2",
+        "DCP izy 8",
+        "NOP zpx 4",
+        "CMP zpx 4",
+        "DEC zpx 6",
+        "DCP zpx 6",
+        "CLD impl 2",
+        "CMP aby 4*",
+        "NOP impl 2",
+        "DCP aby 7",
+        "NOP abx 4*",
+        "CMP abx 4*",
+        "DEC abx 7",
+        "DCP abx 7",
+        "CPX imm 2",
+        "SBC izx 6",
+        "NOP imm 2",
+        "ISC izx 8",
+        "CPX zp 3",
+        "SBC zp 3",
+        "INC zp 5",
+        "ISC zp 5",
+        "INX impl 2",
+        "SBC imm 2",
+        "NOP impl 2",
+        "SBC imm 2",
+        "CPX abs 4",
+        "SBC abs 4",
+        "INC abs 6",
+        "ISC abs 6",
+        "BEQ rel 2*",
+        "SBC izy 5*",
+        "KIL impl 2",
+        "ISC izy 8",
+        "NOP zpx 4",
+        "SBC zpx 4",
+        "INC zpx 6",
+        "ISC zpx 6",
+        "SED impl 2",
+        "SBC aby 4*",
+        "NOP impl 2",
+        "ISC aby 7",
+        "NOP abx 4*",
+        "SBC abx 4*",
+        "INC abx 7",
+        "ISC abx 7",
+    ];
+
+    fn mode(name: &str) -> AddressMode {
+        match name {
+            "impl" => AddressMode::Implicit,
+            "imm" => AddressMode::Immediate,
+            "acc" => AddressMode::Accumulator,
+            "zp" => AddressMode::ZeroPage,
+            "zpx" => AddressMode::ZeroPageX,
+            "zpy" => AddressMode::ZeroPageY,
+            "abs" => AddressMode::Absolute,
+            "abx" => AddressMode::AbsoluteX,
+            "aby" => AddressMode::AbsoluteY,
+            "rel" => AddressMode::Relative,
+            "ind" => AddressMode::Indirect,
+            "izx" => AddressMode::IndirectX,
+            "izy" => AddressMode::IndirectY,
+            _ => panic!("unknown addressing mode {}", name),
+        }
+    }
+
+    #[test]
+    fn table_matches_reference() {
+        for (opcode, entry) in REFERENCE.iter().enumerate() {
+            let fields: Vec<&str> = entry.split(' ').collect();
+            let instruction = get_instruction(opcode as u8);
+            assert_eq!(
+                (
+                    instruction.name,
+                    instruction.address_mode,
+                    instruction.cycles,
+                    instruction.page_penalty
+                ),
+                (
+                    fields[0],
+                    mode(fields[1]),
+                    fields[2].trim_end_matches('*').parse().unwrap(),
+                    fields[2].ends_with('*')
+                ),
+                "opcode {:#04X}",
+                opcode
+            );
+        }
     }
 }
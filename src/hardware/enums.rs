@@ -17,3 +17,22 @@ pub enum Flags {
     Overflow,
     Negative,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Buttons {
+    A,
+    B,
+    Select,
+    Start,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JamPolicy {
+    #[default]
+    Halt, // Freeze the CPU like the hardware does, only a reset recovers
+    Error, // Make step() return an error
+}
@@ -0,0 +1,73 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrqSource {
+    FrameCounter,
+    Dmc,
+    Mapper,
+}
+
+impl IrqSource {
+    fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct Interrupts {
+    nmi_line: bool,    // Level of the PPU's NMI output at the last poll
+    nmi_pending: bool, // Latched on a rising edge, cleared when the CPU takes it
+    irq_sources: u8,   // One bit per IrqSource holding the shared IRQ line
+    nmi_poll: bool,    // What the CPU saw at the end of the previous cycle
+    irq_poll: bool,
+}
+
+impl Interrupts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_irq(&mut self, source: IrqSource, active: bool) {
+        if active {
+            self.irq_sources |= source.mask();
+        } else {
+            self.irq_sources &= !source.mask();
+        }
+    }
+
+    pub fn irq_active(&self) -> bool {
+        self.irq_sources != 0
+    }
+
+    pub fn poll(&mut self, nmi_line: bool, interrupt_disable: bool) {
+        // Runs once per CPU cycle. NMI is edge triggered, IRQ is a level masked by the I flag
+        if nmi_line && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+        self.nmi_line = nmi_line;
+        self.nmi_poll = self.nmi_pending;
+        self.irq_poll = self.irq_active() && !interrupt_disable;
+    }
+
+    pub fn interrupt_requested(&self) -> bool {
+        // Checked after an instruction, the last poll happened before its final cycle
+        self.nmi_poll || self.irq_poll
+    }
+
+    pub fn take_nmi(&mut self) -> bool {
+        // An NMI detected while BRK or IRQ push the status takes over their vector
+        let pending = self.nmi_pending;
+        self.nmi_pending = false;
+        pending
+    }
+
+    pub fn finish_sequence(&mut self) {
+        // The first instruction of a handler always runs before the next interrupt
+        self.nmi_poll = false;
+        self.irq_poll = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.nmi_pending = false;
+        self.nmi_poll = false;
+        self.irq_poll = false;
+    }
+}
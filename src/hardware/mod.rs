@@ -1,16 +1,37 @@
+mod apu;
+mod audio;
 mod bus;
 mod cartridge;
+mod controller;
 mod cpu;
 pub mod enums;
+mod interrupts;
 mod memory;
 mod ppu;
 use Result;
+use audio::{APU_SAMPLE_RATE, Resampler};
+pub use audio::{AudioBuffer, AudioSink, RingBufferSink, WavSink};
+pub use cartridge::{CartridgeError, RomInfo};
+pub use controller::ButtonState;
+use cpu::instructions;
 use cpu::opcode::{self};
 use std::io;
+use std::path::{Path, PathBuf};
+
+pub const SCREEN_WIDTH: u32 = 256;
+pub const SCREEN_HEIGHT: u32 = 240;
+const FDS_BIOS_NAME: &str = "disksys.rom";
+const SAVE_INTERVAL_FRAMES: u32 = 60; // How often battery RAM is checked for changes
 
 pub struct Hardware {
     bus: bus::Bus,
     cpu_cycles: u32,
+    audio_sink: Option<Box<dyn AudioSink>>,
+    resampler: Resampler,
+    save_path: Option<PathBuf>, // Set for ROMs with battery-backed RAM
+    saved_ram: Vec<u8>,         // Battery RAM as last written to disk
+    frames_since_save: u32,
+    jam_policy: enums::JamPolicy,
 }
 
 impl Hardware {
@@ -18,39 +39,36 @@ impl Hardware {
         Self {
             bus: bus::Bus::new(),
             cpu_cycles: 0,
+            audio_sink: None,
+            resampler: Resampler::new(APU_SAMPLE_RATE, 44_100.0),
+            save_path: None,
+            saved_ram: Vec::new(),
+            frames_since_save: 0,
+            jam_policy: enums::JamPolicy::default(),
         }
     }
 
     pub fn step(&mut self, log: bool) -> Result<u32, io::Error> {
         // Execute a single CPU instruction
-        match self.bus.cpu.delayed_interrupt_flag {
-            Some(val) => {
-                self.bus.cpu.set_interrupt_disable(val);
-                self.bus.cpu.delayed_interrupt_flag = None;
-            }
-            _ => {}
-        };
-        let cycles = if self.bus.ppu.get_nmi_pending() {
-            self.bus.ppu.set_nmi_pending(false);
-            let nmi_vector = self.bus.read_word(0xFFFA) as u16;
-            self.bus.cpu.nmi(nmi_vector)
+        if self.bus.cpu.jammed && self.jam_policy == enums::JamPolicy::Error {
+            return Err(io::Error::other(format!(
+                "CPU jammed by KIL at 0x{:04X}",
+                self.bus.cpu.get_counter()
+            )));
+        }
+        let start = self.bus.cycles;
+        let stalled = self.bus.stall_cycles;
+        if self.bus.cpu.jammed {
+            // Interrupts are ignored too, but the rest of the console keeps running
+            self.bus.tick();
+            self.bus.tick();
+        } else if self.bus.interrupts.interrupt_requested() {
+            // Polled before the last cycle of the previous instruction
+            instructions::interrupt_request(&mut self.bus);
         } else {
             // Fetch and decode instruction
             let opcode = self.bus.read_instruct();
-            // get time spent on this part
-            let instruction = match opcode::get_instruction(opcode) {
-                Some(instr) => instr,
-                None => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!(
-                            "Invalid opcode [0x{:04X}]: 0x{:02X}",
-                            self.bus.cpu.get_counter(),
-                            opcode
-                        ),
-                    ));
-                }
-            };
+            let instruction = opcode::get_instruction(opcode);
 
             if log {
                 let (instruct, _) = self
@@ -59,16 +77,27 @@ impl Hardware {
                 println!("[0x{:04X}] {}", self.bus.cpu.get_counter(), instruct);
             }
 
-            (instruction.execute)(&mut self.bus, instruction.address_mode)
-        };
-        self.cpu_cycles += cycles as u32;
-
-        let cycle_count = cycles * 3;
+            (instruction.execute)(&mut self.bus, instruction.address_mode);
+            // The table's cycle counts double as a check on the bus accesses
+            let taken = self.bus.cycles - start - (self.bus.stall_cycles - stalled);
+            debug_assert!(
+                (instruction.cycles as u64..=instruction.max_cycles() as u64).contains(&taken),
+                "{} {} took {} cycles",
+                instruction.name,
+                instruction.address_mode,
+                taken
+            );
+        }
+        self.bus.run_oam_dma();
 
-        // Run PPU ticks
-        for _ in 0..cycle_count {
-            self.bus.ppu.tick();
+        for sample in self.bus.audio_samples.drain(..) {
+            if let Some(sink) = self.audio_sink.as_mut() {
+                self.resampler
+                    .push(sample, |sample| sink.write_sample(sample));
+            }
         }
+        let cycles = (self.bus.cycles - start) as u32;
+        self.cpu_cycles += cycles;
         if self.cpu_cycles >= 29780 {
             // Reset CPU cycles after a frame
             let cycle = self.cpu_cycles;
@@ -81,12 +110,23 @@ impl Hardware {
 
     pub fn tick(&mut self) -> Result<(), io::Error> {
         // Execute a single CPU instruction and update PPU
+        if let Some(sink) = self.audio_sink.as_ref() {
+            self.resampler.set_adjustment(sink.rate_adjustment());
+        }
         loop {
             // Update the PPU state
             if self.step(false)? >= 29780 {
-                return Ok(());
+                break;
             }
         }
+        self.frames_since_save += 1;
+        if self.frames_since_save >= SAVE_INTERVAL_FRAMES {
+            self.frames_since_save = 0;
+            if let Err(err) = self.save_battery() {
+                eprintln!("Could not write save file: {}", err);
+            }
+        }
+        Ok(())
     }
 
     pub fn get_memory_dump(&self, start: usize, size: usize) -> String {
@@ -97,7 +137,7 @@ impl Hardware {
                 .cartridge
                 .borrow()
                 .mapper
-                .cpu_read(i as u16)
+                .peek(i as u16)
                 .unwrap_or(0);
             if i % 32 == 0 {
                 if i != 0 {
@@ -145,12 +185,121 @@ impl Hardware {
         image
     }
 
-    pub fn load_rom(&mut self, file_path: &str) -> Result<(), io::Error> {
-        self.bus.cartridge.borrow_mut().load_ines_rom(file_path)?;
+    pub fn frame(&self) -> &[u8] {
+        // 256x240 RGBA image of the last rendered frame
+        self.bus.ppu.get_frame_buffer()
+    }
+
+    pub fn set_audio_sink(&mut self, sink: Box<dyn AudioSink>) {
+        self.resampler = Resampler::new(APU_SAMPLE_RATE, sink.sample_rate() as f64);
+        self.audio_sink = Some(sink);
+    }
+
+    pub fn flush_audio(&mut self) -> Result<(), io::Error> {
+        match self.audio_sink.as_mut() {
+            Some(sink) => sink.flush(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_rom(&mut self, file_path: &str) -> Result<(), CartridgeError> {
+        let data = std::fs::read(file_path)?;
+        let bios_path = Path::new(file_path).with_file_name(FDS_BIOS_NAME);
+        if self.bus.cartridge.borrow().fds_bios.is_none() && bios_path.exists() {
+            // Disk images pick up the BIOS from their own directory
+            self.load_fds_bios(&bios_path)?;
+        }
+        self.load_rom_bytes(&data)?;
+        if self.bus.cartridge.borrow().info.battery {
+            // game.nes keeps its battery RAM in game.sav next to it
+            let save_path = Path::new(file_path).with_extension("sav");
+            self.load_battery(&save_path)?;
+            self.save_path = Some(save_path);
+        }
+        Ok(())
+    }
+
+    pub fn load_rom_bytes(&mut self, data: &[u8]) -> Result<(), CartridgeError> {
+        // Flush the previous game before its RAM is replaced
+        if let Err(err) = self.save_battery() {
+            eprintln!("Could not write save file: {}", err);
+        }
+        self.save_path = None;
+        self.saved_ram.clear();
+
+        // .zip and .gz containers are detected by their magic bytes
+        let rom = cartridge::unpack(data)?;
+        self.bus.cartridge.borrow_mut().load_bytes(&rom)?;
         self.bus.reset();
         Ok(())
     }
 
+    pub fn save_battery(&mut self) -> Result<(), io::Error> {
+        // Writes battery RAM to the .sav file, skipped when nothing changed
+        let Some(path) = self.save_path.as_ref() else {
+            return Ok(());
+        };
+        let cartridge = self.bus.cartridge.borrow();
+        let Some(ram) = cartridge.mapper.prg_ram() else {
+            return Ok(());
+        };
+        if ram != self.saved_ram.as_slice() {
+            std::fs::write(path, ram)?;
+            self.saved_ram = ram.to_vec();
+        }
+        Ok(())
+    }
+
+    fn load_battery(&mut self, path: &Path) -> Result<(), io::Error> {
+        let mut cartridge = self.bus.cartridge.borrow_mut();
+        let Some(ram) = cartridge.mapper.prg_ram_mut() else {
+            return Ok(());
+        };
+        match std::fs::read(path) {
+            Ok(data) => {
+                // Tolerate save files of a different size, copy what fits
+                let len = data.len().min(ram.len());
+                ram[..len].copy_from_slice(&data[..len]);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        // The trainer goes on top of the save, like it would at boot
+        cartridge.apply_trainer();
+        if let Some(ram) = cartridge.mapper.prg_ram() {
+            self.saved_ram = ram.to_vec();
+        }
+        Ok(())
+    }
+
+    pub fn load_fds_bios(&mut self, path: &Path) -> Result<(), CartridgeError> {
+        let bios = std::fs::read(path)?;
+        self.bus.cartridge.borrow_mut().set_fds_bios(bios)
+    }
+
+    pub fn disk_sides(&self) -> usize {
+        self.bus.cartridge.borrow().mapper.disk_sides()
+    }
+
+    pub fn insert_disk(&mut self, side: Option<usize>) {
+        self.bus.cartridge.borrow_mut().mapper.insert_disk(side);
+    }
+
+    pub fn rom_info(&self) -> RomInfo {
+        // Header information of the loaded ROM
+        self.bus.cartridge.borrow().info.clone()
+    }
+
+    pub fn set_jam_policy(&mut self, policy: enums::JamPolicy) {
+        self.jam_policy = policy;
+    }
+
+    pub fn set_buttons(&mut self, port: usize, buttons: ButtonState) {
+        if let Some(controller) = self.bus.controllers.get_mut(port) {
+            controller.set_buttons(buttons);
+        }
+    }
+
     pub fn get_cpu_reg(&self, register: enums::Registers) -> u8 {
         self.bus.cpu.get(register)
     }
@@ -179,3 +328,119 @@ impl Hardware {
         self.bus.ppu.get_palette_rgba()
     }
 }
+
+impl Drop for Hardware {
+    fn drop(&mut self) {
+        if let Err(err) = self.save_battery() {
+            eprintln!("Could not write save file: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hardware_with_program(program: &[u8]) -> Hardware {
+        // NROM image running `program` from $8000
+        let mut rom = b"NES\x1A\x01\x01\x00\x00".to_vec();
+        rom.resize(16, 0);
+        let mut prg = vec![0xEA; 0x4000];
+        prg[..program.len()].copy_from_slice(program);
+        prg[0x3FFC..0x3FFE].copy_from_slice(&[0x00, 0x80]);
+        rom.extend(prg);
+        rom.extend(vec![0; 0x2000]);
+
+        let mut hardware = Hardware::new();
+        hardware.load_rom_bytes(&rom).unwrap();
+        hardware
+    }
+
+    #[test]
+    fn jam_halt_keeps_the_console_running() {
+        let mut hardware = hardware_with_program(&[0x02]);
+        hardware.set_jam_policy(enums::JamPolicy::Halt);
+        hardware.step(false).unwrap();
+        for _ in 0..10 {
+            let start = hardware.bus.cycles;
+            assert!(hardware.step(false).is_ok());
+            assert_eq!(hardware.bus.cycles - start, 2);
+            assert_eq!(hardware.get_pc(), 0x8000);
+        }
+    }
+
+    #[test]
+    fn jam_error_stops_step() {
+        let mut hardware = hardware_with_program(&[0x02]);
+        hardware.set_jam_policy(enums::JamPolicy::Error);
+        hardware.step(false).unwrap();
+        let err = hardware.step(false).unwrap_err();
+        assert_eq!(err.to_string(), "CPU jammed by KIL at 0x8000");
+        assert!(hardware.step(false).is_err());
+    }
+
+    #[test]
+    fn reset_clears_a_jam() {
+        let mut hardware = hardware_with_program(&[0x02]);
+        hardware.set_jam_policy(enums::JamPolicy::Error);
+        hardware.step(false).unwrap();
+        hardware.bus.reset();
+        assert!(hardware.step(false).is_ok());
+    }
+
+    #[test]
+    fn dummy_read_below_prg_ram_is_open_bus() {
+        // LDX #$20, STA $4000,X: the indexed store dummy-reads $4020 first
+        let mut hardware = hardware_with_program(&[0xA2, 0x20, 0x9D, 0x00, 0x40, 0x02]);
+        for _ in 0..3 {
+            hardware.step(false).unwrap();
+        }
+        assert_eq!(hardware.get_pc(), 0x8005);
+        assert!(hardware.bus.cpu.jammed);
+        assert_eq!(hardware.bus.read(0x5000), 0x50);
+    }
+
+    #[test]
+    fn unmapped_cartridge_read_is_open_bus() {
+        // No ROM loaded, so nothing answers at $8000
+        let mut hardware = Hardware::new();
+        assert_eq!(hardware.bus.read(0x8000), 0x80);
+    }
+
+    fn nametable_text(hardware: &mut Hardware) -> String {
+        // Read the first nametable back through PPUADDR/PPUDATA with rendering
+        // off and +1 increments, the first read only fills the buffer
+        hardware.bus.write(0x2000, 0x00);
+        hardware.bus.write(0x2001, 0x00);
+        hardware.bus.write(0x2006, 0x20);
+        hardware.bus.write(0x2006, 0x00);
+        hardware.bus.read(0x2007);
+        (0..0x3C0)
+            .map(|_| hardware.bus.read(0x2007))
+            .map(|byte| {
+                if byte.is_ascii_graphic() {
+                    byte as char
+                } else {
+                    ' '
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cpu_dummy_reads_passes() {
+        let mut hardware = Hardware::new();
+        hardware
+            .load_rom(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/roms/cpu_dummy_reads.nes"
+            ))
+            .unwrap();
+        // The result is on screen after about 60 frames
+        for _ in 0..120 {
+            hardware.tick().unwrap();
+        }
+        let text = nametable_text(&mut hardware);
+        assert!(text.contains("Passed"), "{}", text.trim());
+    }
+}
@@ -1,22 +1,26 @@
+use crate::hardware::cartridge::ScreenMirroring;
+
 #[derive(Debug, Clone)]
-pub struct Memory {
-    palette: [u8; 32],
-    frame_buffer: Vec<u8>,
+pub struct Vram {
+    nametables: [u8; 2048], // 2KB of console CIRAM
 }
 
-impl Memory {
+impl Vram {
     pub fn new() -> Self {
-        Memory {
-            palette: [0; 32],
-            frame_buffer: vec![0; 256 * 240 * 4],
+        Vram {
+            nametables: [0; 2048],
         }
     }
 
-    pub fn read_palette(&self, address: u16) -> Option<u8> {
-        self.palette.get(address as usize).copied()
+    pub fn read(&self, addr: u16, mirroring: ScreenMirroring) -> u8 {
+        self.nametables[mirroring.nametable_index(addr)]
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8, mirroring: ScreenMirroring) {
+        self.nametables[mirroring.nametable_index(addr)] = value;
     }
 
-    pub fn write_palette(&mut self, address: u16, value: u8) {
-        self.palette[address as usize] = value;
+    pub fn reset(&mut self) {
+        self.nametables.fill(0);
     }
 }
@@ -3,6 +3,7 @@ mod palette_map;
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use super::cartridge::Cartridge;
+use memory::Vram;
 
 #[derive(Debug, Clone)]
 pub struct Ppu {
@@ -23,11 +24,30 @@ pub struct Ppu {
     pub temp_addr: u16, // temp VRAM address
     pub fine_x: u8,
     pub buffered_data: u8, // used for delayed PPU reads
+    pub odd_frame: bool,
+    dot_count: u64, // PPU dots since power on, lets mappers time A12 edges
 
+    // Background rendering pipeline
+    bg_next_tile_id: u8,
+    bg_next_tile_attr: u8,
+    bg_next_tile_lsb: u8,
+    bg_next_tile_msb: u8,
+    bg_shifter_pattern_lo: u16,
+    bg_shifter_pattern_hi: u16,
+    bg_shifter_attrib_lo: u16,
+    bg_shifter_attrib_hi: u16,
+
+    // Sprite rendering pipeline
+    secondary_oam: [u8; 32], // sprites selected for the next scanline
+    sprite_count: u8,
+    sprite_zero_in_line: bool, // OAM sprite 0 occupies secondary OAM slot 0
+    sprite_pattern_lo: [u8; 8],
+    sprite_pattern_hi: [u8; 8],
+
+    vram: Vram,
     palette: [u8; 32],
     frame_buffer: Vec<u8>,
     cartridge: Rc<RefCell<Cartridge>>,
-    nmi_pending: bool,
 
     palette_rgba: [[u8; 4]; 32],     // Map for NES colors
     color_map: HashMap<u8, [u8; 4]>, // Map for NES colors
@@ -52,10 +72,25 @@ impl Ppu {
             temp_addr: 0x0000,   // Temp VRAM address
             fine_x: 0x00,        // Fine X scroll
             buffered_data: 0x00, // PPUDATA read buffer
+            odd_frame: false,
+            dot_count: 0,
+            bg_next_tile_id: 0x00,
+            bg_next_tile_attr: 0x00,
+            bg_next_tile_lsb: 0x00,
+            bg_next_tile_msb: 0x00,
+            bg_shifter_pattern_lo: 0x0000,
+            bg_shifter_pattern_hi: 0x0000,
+            bg_shifter_attrib_lo: 0x0000,
+            bg_shifter_attrib_hi: 0x0000,
+            secondary_oam: [0xFF; 32],
+            sprite_count: 0,
+            sprite_zero_in_line: false,
+            sprite_pattern_lo: [0; 8],
+            sprite_pattern_hi: [0; 8],
             cartridge,
+            vram: Vram::new(),
             palette: [0; 32], // Palette RAM: Zero-filled (or random)
             frame_buffer: vec![0; 256 * 240 * 4], // Black screen
-            nmi_pending: false, // No NMI pending
             palette_rgba: [[0; 4]; 32], // RGBA palette
             color_map: palette_map::get_color_map(),
         }
@@ -78,118 +113,389 @@ impl Ppu {
         self.temp_addr = 0x0000; // Clear temp address
         self.fine_x = 0x00; // Clear fine X
         self.buffered_data = 0x00; // Clear read buffer
+        self.odd_frame = false;
+        self.bg_next_tile_id = 0x00;
+        self.bg_next_tile_attr = 0x00;
+        self.bg_next_tile_lsb = 0x00;
+        self.bg_next_tile_msb = 0x00;
+        self.bg_shifter_pattern_lo = 0x0000; // Clear background shifters
+        self.bg_shifter_pattern_hi = 0x0000;
+        self.bg_shifter_attrib_lo = 0x0000;
+        self.bg_shifter_attrib_hi = 0x0000;
+        self.secondary_oam = [0xFF; 32]; // Clear sprite pipeline
+        self.sprite_count = 0;
+        self.sprite_zero_in_line = false;
+        self.sprite_pattern_lo = [0; 8];
+        self.sprite_pattern_hi = [0; 8];
+        self.vram.reset();
         //self.pallette unchanged    // Palette typically not cleared on reset
         //self.frame_buffer unchanged // Frame buffer typically not cleared
-        self.nmi_pending = false; // Clear pending NMI
     }
 
     pub fn tick(&mut self) {
+        let rendering = self.rendering_enabled();
+        if self.scanline < 240 || self.scanline == 261 {
+            // Visible scanlines and the pre-render scanline share the fetch pipeline
+            if self.scanline == 261 && self.cycle == 1 {
+                // End of VBlank, clear VBlank flag
+                self.status &= 0x1f; // Clear VBlank and sprite 0 hit flags
+            }
+            if rendering {
+                self.background_cycle();
+                self.sprite_cycle();
+            }
+            if self.scanline < 240 && self.cycle >= 1 && self.cycle <= 256 {
+                self.render_pixel();
+            }
+        } else if self.scanline == 241 && self.cycle == 1 {
+            // Start of VBlank, set VBlank flag
+            self.status |= 0x80; // Set VBlank flag
+            self.frame_complete = true; // Indicate frame completion
+        }
+
+        self.dot_count += 1;
         self.cycle += 1;
-        if self.scanline < 240 {
-            // Visible scanlines
-            if self.cycle >= 1 && self.cycle <= 256 {
-                // Render pixel logic
-                let nt_addr = (self.vram_addr >> 10) & 0x03;
-                let tile_id = self.read_vram(self.vram_addr).unwrap_or(0);
-                let attr_addr = 0x23C0
-                    | (self.vram_addr & 0x0C00)
-                    | ((self.vram_addr >> 4) & 0x38)
-                    | ((self.vram_addr >> 2) & 0x07);
-                let attr = self.read_vram(attr_addr).unwrap_or(0);
-                let mut pallette_index = attr;
-                if (self.vram_addr & 0x0040) != 0 {
-                    // bottem half
-                    pallette_index >>= 4;
+        if self.scanline == 261 && self.cycle == 340 && self.odd_frame && rendering {
+            // Odd frames skip the last dot of the pre-render scanline
+            self.cycle = 341;
+        }
+        if self.cycle >= 341 {
+            self.cycle = 0;
+            self.scanline += 1;
+            if self.scanline >= 262 {
+                self.scanline = 0; // Reset scanline after reaching the end
+                self.odd_frame = !self.odd_frame;
+            }
+        }
+    }
+
+    fn rendering_enabled(&self) -> bool {
+        (self.mask & 0x18) != 0
+    }
+
+    fn background_cycle(&mut self) {
+        // Fetch tile data in 8-dot groups: nametable, attribute, pattern low, pattern high
+        if (self.cycle >= 2 && self.cycle <= 257) || (self.cycle >= 321 && self.cycle <= 337) {
+            self.update_shifters();
+            match (self.cycle - 1) % 8 {
+                0 => {
+                    self.load_background_shifters();
+                    let nt_addr = 0x2000 | (self.vram_addr & 0x0FFF);
+                    self.bg_next_tile_id = self.read_vram(nt_addr).unwrap_or(0);
                 }
-                if (self.vram_addr & 0x0002) != 0 {
-                    // Right half
-                    pallette_index >>= 2;
+                2 => {
+                    let attr_addr = 0x23C0
+                        | (self.vram_addr & 0x0C00)
+                        | ((self.vram_addr >> 4) & 0x38)
+                        | ((self.vram_addr >> 2) & 0x07);
+                    let mut attr = self.read_vram(attr_addr).unwrap_or(0);
+                    if (self.vram_addr & 0x0040) != 0 {
+                        // Bottom half
+                        attr >>= 4;
+                    }
+                    if (self.vram_addr & 0x0002) != 0 {
+                        // Right half
+                        attr >>= 2;
+                    }
+                    self.bg_next_tile_attr = attr & 0x03;
+                }
+                4 => {
+                    let addr = self.background_pattern_addr();
+                    self.bg_next_tile_lsb = self.read_vram(addr).unwrap_or(0);
                 }
+                6 => {
+                    let addr = self.background_pattern_addr() + 8;
+                    self.bg_next_tile_msb = self.read_vram(addr).unwrap_or(0);
+                }
+                7 => self.increment_scroll_x(),
+                _ => {}
+            }
+        }
 
-                let pallette_selector = (pallette_index & 0x03) << 2;
-
-                // TODO: Fetch tile data and render pixel
-                if self.cycle % 8 == 0 && self.cycle <= 256 {
-                    // Increment coarse X (bits 0-4)
-                    if self.vram_addr & 0x001F == 0x001F {
-                        // Coarse X == 31
-                        self.vram_addr &= !0x001F; // Reset coarse X
-                        self.vram_addr ^= 0x0400; // Flip nametable X (bit 10)
-                    } else {
-                        self.vram_addr += 1; // Increment coarse X
-                    }
+        if self.cycle == 256 {
+            self.increment_scroll_y();
+        } else if self.cycle == 257 {
+            // Reset horizontal bits of vram_addr from temp_addr (for next scanline's start)
+            self.vram_addr = (self.vram_addr & 0x7BE0) | (self.temp_addr & 0x041F);
+        } else if self.cycle == 338 || self.cycle == 340 {
+            // Unused nametable fetches at the end of the scanline
+            let nt_addr = 0x2000 | (self.vram_addr & 0x0FFF);
+            self.bg_next_tile_id = self.read_vram(nt_addr).unwrap_or(0);
+        }
+
+        if self.scanline == 261 && self.cycle >= 280 && self.cycle <= 304 {
+            // Reload vertical bits of vram_addr from temp_addr before the new frame
+            self.vram_addr = (self.vram_addr & 0x041F) | (self.temp_addr & 0x7BE0);
+        }
+    }
+
+    fn background_pattern_addr(&self) -> u16 {
+        let table = ((self.control as u16 >> 4) & 0x01) << 12;
+        let fine_y = (self.vram_addr >> 12) & 0x07;
+        table + ((self.bg_next_tile_id as u16) << 4) + fine_y
+    }
+
+    fn increment_scroll_x(&mut self) {
+        // Increment coarse X (bits 0-4)
+        if self.vram_addr & 0x001F == 0x001F {
+            // Coarse X == 31
+            self.vram_addr &= !0x001F; // Reset coarse X
+            self.vram_addr ^= 0x0400; // Flip nametable X (bit 10)
+        } else {
+            self.vram_addr += 1; // Increment coarse X
+        }
+    }
+
+    fn increment_scroll_y(&mut self) {
+        if (self.vram_addr & 0x7000) != 0x7000 {
+            self.vram_addr += 0x1000; // Increment fine Y (bit 12-14)
+            return;
+        }
+        // Fine Y == 7
+        self.vram_addr &= !0x7000; // Reset fine Y
+        let mut coarse_y = (self.vram_addr & 0x03E0) >> 5;
+        if coarse_y == 29 {
+            coarse_y = 0;
+            self.vram_addr ^= 0x0800; // Flip nametable Y (bit 11)
+        } else if coarse_y == 31 {
+            coarse_y = 0; // No flip if overflow beyond 29 (attribute rows)
+        } else {
+            coarse_y += 1;
+        }
+        self.vram_addr = (self.vram_addr & !0x03E0) | (coarse_y << 5);
+    }
+
+    fn load_background_shifters(&mut self) {
+        // Next tile goes into the low byte, the current tile is already in the high byte
+        self.bg_shifter_pattern_lo =
+            (self.bg_shifter_pattern_lo & 0xFF00) | self.bg_next_tile_lsb as u16;
+        self.bg_shifter_pattern_hi =
+            (self.bg_shifter_pattern_hi & 0xFF00) | self.bg_next_tile_msb as u16;
+        let attr_lo = if self.bg_next_tile_attr & 0x01 != 0 {
+            0xFF
+        } else {
+            0x00
+        };
+        let attr_hi = if self.bg_next_tile_attr & 0x02 != 0 {
+            0xFF
+        } else {
+            0x00
+        };
+        self.bg_shifter_attrib_lo = (self.bg_shifter_attrib_lo & 0xFF00) | attr_lo;
+        self.bg_shifter_attrib_hi = (self.bg_shifter_attrib_hi & 0xFF00) | attr_hi;
+    }
+
+    fn update_shifters(&mut self) {
+        if (self.mask & 0x08) != 0 {
+            self.bg_shifter_pattern_lo <<= 1;
+            self.bg_shifter_pattern_hi <<= 1;
+            self.bg_shifter_attrib_lo <<= 1;
+            self.bg_shifter_attrib_hi <<= 1;
+        }
+    }
+
+    fn background_pixel(&self, x: u16) -> (u8, u8) {
+        // Returns (pixel, palette) for the background at the current dot
+        if (self.mask & 0x08) == 0 || (x < 8 && (self.mask & 0x02) == 0) {
+            return (0, 0);
+        }
+        let bit_mux = 0x8000 >> self.fine_x;
+        let p0 = ((self.bg_shifter_pattern_lo & bit_mux) != 0) as u8;
+        let p1 = ((self.bg_shifter_pattern_hi & bit_mux) != 0) as u8;
+        let pal0 = ((self.bg_shifter_attrib_lo & bit_mux) != 0) as u8;
+        let pal1 = ((self.bg_shifter_attrib_hi & bit_mux) != 0) as u8;
+        ((p1 << 1) | p0, (pal1 << 1) | pal0)
+    }
+
+    fn sprite_height(&self) -> u16 {
+        if (self.control & 0x20) != 0 { 16 } else { 8 }
+    }
+
+    fn sprite_cycle(&mut self) {
+        if self.cycle == 257 {
+            self.evaluate_sprites();
+        }
+        if self.cycle >= 257 && self.cycle <= 320 {
+            // OAMADDR is reset during sprite tile loading
+            self.oam_addr = 0;
+            let index = ((self.cycle - 257) / 8) as usize;
+            match (self.cycle - 257) % 8 {
+                5 => {
+                    let addr = self.sprite_pattern_addr(index);
+                    let data = self.read_vram(addr).unwrap_or(0);
+                    self.sprite_pattern_lo[index] = self.flip_sprite_row(index, data);
                 }
-                if (self.vram_addr & 0x7000) == 0x7000 {
-                    // Fine Y == 7
-                    self.vram_addr &= !0x7000; // Reset fine Y
-                    let mut coarse_y = (self.vram_addr & 0x03E0) >> 5;
-                    if coarse_y == 29 {
-                        coarse_y = 0;
-                        self.vram_addr ^= 0x0800; // Flip nametable Y (bit 11)
-                    } else if coarse_y == 31 {
-                        coarse_y = 0; // No flip if overflow beyond 29 (safety)
-                    } else {
-                        coarse_y += 1;
-                    }
-                    self.vram_addr = (self.vram_addr & !0x03E0) | (coarse_y << 5);
-                } else {
-                    self.vram_addr += 0x1000; // Increment fine Y (bit 12-14)
+                7 => {
+                    let addr = self.sprite_pattern_addr(index) + 8;
+                    let data = self.read_vram(addr).unwrap_or(0);
+                    self.sprite_pattern_hi[index] = self.flip_sprite_row(index, data);
                 }
-            } else if self.cycle == 257 {
-                // Reset horizontal bits of vram_addr from temp_addr (for next scanline's start)
-                self.vram_addr = (self.vram_addr & 0x7BE0) | (self.temp_addr & 0x041F);
-            } else if self.cycle == 328 {
+                _ => {}
             }
-        } else if self.scanline == 240 {
-        } else if self.scanline == 241 {
-            if self.cycle == 1 {
-                // Start of VBlank, set VBlank flag
-                self.status |= 0x80; // Set VBlank flag
-                self.frame_complete = true; // Indicate frame completion
-                if (self.control & 0x80) != 0 {
-                    // If NMI is enabled, trigger NMI
-                    self.nmi_pending = true;
-                    println!("NMI triggered at scanline 241, cycle 1");
+        }
+    }
+
+    fn evaluate_sprites(&mut self) {
+        // Select up to 8 sprites from primary OAM that fall on the next scanline
+        self.secondary_oam = [0xFF; 32];
+        self.sprite_count = 0;
+        self.sprite_zero_in_line = false;
+        if self.scanline == 261 {
+            // Sprites are never drawn on scanline 0
+            return;
+        }
+        let height = self.sprite_height();
+        let mut n = 0;
+        while n < 64 && self.sprite_count < 8 {
+            let y = self.oam_data[n * 4] as u16;
+            if self.scanline.wrapping_sub(y) < height {
+                if n == 0 {
+                    self.sprite_zero_in_line = true;
                 }
+                let slot = self.sprite_count as usize * 4;
+                self.secondary_oam[slot..slot + 4]
+                    .copy_from_slice(&self.oam_data[n * 4..n * 4 + 4]);
+                self.sprite_count += 1;
             }
-        } else if self.scanline > 241 && self.scanline < 261 {
-            // Post-render scanlines
-        } else if self.scanline == 261 {
-            if self.cycle == 1 {
-                // End of VBlank, clear VBlank flag
-                self.status &= 0x1f; // Clear VBlank and sprite 0 hit flags
-            } else if self.cycle >= 280 && self.cycle < 304 {
-                self.vram_addr = (self.vram_addr & 0x041F) | (self.temp_addr & 0x7BE0);
-            } else if self.cycle == 257 {
-                self.vram_addr = (self.vram_addr & 0x7BE0) | (self.temp_addr & 0x041F);
+            n += 1;
+        }
+
+        // Once secondary OAM is full the hardware keeps scanning, but it increments the
+        // byte offset along with the sprite index, so it reads tile/attr/x bytes as Y.
+        let mut m = 0;
+        while n < 64 {
+            let y = self.oam_data[n * 4 + m] as u16;
+            if self.scanline.wrapping_sub(y) < height {
+                self.status |= 0x20; // Set sprite overflow flag
+                break;
             }
+            n += 1;
+            m = (m + 1) & 0x03;
         }
-        if self.cycle >= 341 {
-            self.cycle = 0;
-            self.scanline += 1;
-            if self.scanline >= 262 {
-                self.scanline = 0; // Reset scanline after reaching the end
+    }
+
+    fn sprite_pattern_addr(&self, index: usize) -> u16 {
+        let y = self.secondary_oam[index * 4] as u16;
+        let tile = self.secondary_oam[index * 4 + 1] as u16;
+        let attr = self.secondary_oam[index * 4 + 2];
+        let height = self.sprite_height();
+
+        // Unused slots still fetch tile $FF
+        let mut row = if index < self.sprite_count as usize {
+            self.scanline.wrapping_sub(y) & (height - 1)
+        } else {
+            0
+        };
+        if (attr & 0x80) != 0 {
+            // Vertical flip
+            row = height - 1 - row;
+        }
+
+        if height == 16 {
+            // 8x16 sprites select the pattern table with bit 0 of the tile index
+            let table = (tile & 0x01) << 12;
+            let tile = (tile & 0xFE) + (row >> 3);
+            table + (tile << 4) + (row & 0x07)
+        } else {
+            let table = ((self.control as u16 >> 3) & 0x01) << 12;
+            table + (tile << 4) + row
+        }
+    }
+
+    fn flip_sprite_row(&self, index: usize, data: u8) -> u8 {
+        if index >= self.sprite_count as usize {
+            // Unused slots are transparent
+            0
+        } else if (self.secondary_oam[index * 4 + 2] & 0x40) != 0 {
+            // Horizontal flip
+            data.reverse_bits()
+        } else {
+            data
+        }
+    }
+
+    fn sprite_pixel(&self, x: u16) -> (u8, u8, bool, usize) {
+        // Returns (pixel, palette, behind_background, slot) for the first opaque sprite at x
+        if (self.mask & 0x10) == 0 || (x < 8 && (self.mask & 0x04) == 0) {
+            return (0, 0, false, 0);
+        }
+        for index in 0..self.sprite_count as usize {
+            let sprite_x = self.secondary_oam[index * 4 + 3] as u16;
+            if x < sprite_x || x >= sprite_x + 8 {
+                continue;
+            }
+            let bit = 7 - (x - sprite_x);
+            let p0 = (self.sprite_pattern_lo[index] >> bit) & 0x01;
+            let p1 = (self.sprite_pattern_hi[index] >> bit) & 0x01;
+            let pixel = (p1 << 1) | p0;
+            if pixel != 0 {
+                let attr = self.secondary_oam[index * 4 + 2];
+                return (pixel, (attr & 0x03) + 4, (attr & 0x20) != 0, index);
             }
         }
+        (0, 0, false, 0)
+    }
+
+    fn render_pixel(&mut self) {
+        let x = self.cycle - 1;
+        let y = self.scanline;
+        let (bg_pixel, bg_palette) = self.background_pixel(x);
+        let (fg_pixel, fg_palette, behind, slot) = self.sprite_pixel(x);
+
+        // Sprite 0 hit: opaque sprite 0 over opaque background, never at x=255.
+        // Left-edge clipping is already applied by background_pixel/sprite_pixel.
+        if bg_pixel != 0 && fg_pixel != 0 && slot == 0 && self.sprite_zero_in_line && x != 255 {
+            self.status |= 0x40; // Set sprite 0 hit flag
+        }
+
+        let (pixel, palette) = match (bg_pixel, fg_pixel) {
+            (0, 0) => (0, 0),
+            (0, _) => (fg_pixel, fg_palette),
+            (_, 0) => (bg_pixel, bg_palette),
+            _ if behind => (bg_pixel, bg_palette),
+            _ => (fg_pixel, fg_palette),
+        };
+
+        let palette_addr = if pixel == 0 {
+            0 // Universal background color
+        } else {
+            (palette << 2) | pixel
+        };
+        let grayscale = if (self.mask & 0x01) != 0 { 0x30 } else { 0x3F };
+        let color_index = self.palette[palette_addr as usize] & grayscale;
+        let color = self.get_nes_color(color_index);
+
+        let offset = ((y as usize) * 256 + x as usize) * 4;
+        self.frame_buffer[offset..offset + 4].copy_from_slice(&color);
     }
 
-    pub fn get_nmi_pending(&self) -> bool {
-        self.nmi_pending
+    pub fn get_frame_buffer(&self) -> &[u8] {
+        &self.frame_buffer
     }
 
-    pub fn set_nmi_pending(&mut self, status: bool) {
-        self.nmi_pending = status;
+    pub fn nmi_line(&self) -> bool {
+        // NMI output, enabling it during VBlank raises it again
+        (self.status & 0x80) != 0 && (self.control & 0x80) != 0
     }
 
     fn read_vram(&mut self, addr: u16) -> Option<u8> {
         let mapped_addr = addr & 0x3FFF; // Mask to 14 bits
-        if mapped_addr < 0x3F00 {
+        if mapped_addr < 0x2000 {
             // CHR ROM
+            self.observe_address(mapped_addr);
             self.cartridge.borrow().mapper.ppu_read(mapped_addr)
-        } else if mapped_addr >= 0x3F00 && mapped_addr < 0x4000 {
+        } else if mapped_addr < 0x3F00 {
+            // Nametables, unless the board maps them itself
+            self.observe_address(mapped_addr);
+            let cartridge = self.cartridge.borrow();
+            let value = cartridge
+                .nametable_read(mapped_addr)
+                .unwrap_or_else(|| self.vram.read(mapped_addr, cartridge.mirroring));
+            Some(value)
+        } else if (0x3F00..0x4000).contains(&mapped_addr) {
             // Palette memory
-            let address = (mapped_addr - 0x3F00) % 0x20;
-            self.palette.get(address as usize).copied()
+            let address = Self::palette_address(mapped_addr);
+            self.palette.get(address).copied()
         } else {
             // Invalid address
             eprintln!("PPU Read: Invalid address 0x{:04X}", addr);
@@ -199,22 +505,47 @@ impl Ppu {
 
     fn write_vram(&mut self, addr: u16, value: u8) {
         let mapped_addr = addr & 0x3FFF; // Mask to 14 bits
-        if mapped_addr < 0x3F00 {
+        if mapped_addr < 0x2000 {
             // CHR ROM
+            self.observe_address(mapped_addr);
             self.cartridge
                 .borrow_mut()
                 .mapper
                 .ppu_write(mapped_addr, value);
-        } else if mapped_addr >= 0x3F00 && mapped_addr < 0x4000 {
+        } else if mapped_addr < 0x3F00 {
+            // Nametables, unless the board maps them itself
+            self.observe_address(mapped_addr);
+            let mut cartridge = self.cartridge.borrow_mut();
+            if !cartridge.nametable_write(mapped_addr, value) {
+                self.vram.write(mapped_addr, value, cartridge.mirroring);
+            }
+        } else if (0x3F00..0x4000).contains(&mapped_addr) {
             // Palette memory
-            let address = (mapped_addr - 0x3F00) % 0x20;
-            self.set_palette(address as usize, value);
+            let address = Self::palette_address(mapped_addr);
+            self.set_palette(address, value);
         } else {
             // Invalid address
             eprintln!("PPU Write: Invalid address 0x{:04X}", addr);
         }
     }
 
+    fn observe_address(&self, addr: u16) {
+        self.cartridge
+            .borrow_mut()
+            .mapper
+            .observe_ppu_address(addr, self.dot_count);
+    }
+
+    fn palette_address(addr: u16) -> usize {
+        let address = (addr - 0x3F00) % 0x20;
+        // $3F10/$3F14/$3F18/$3F1C mirror the background entries
+        if address >= 0x10 && address & 0x03 == 0 {
+            (address - 0x10) as usize
+        } else {
+            address as usize
+        }
+    }
+
     pub fn read_register(&mut self, addr: u16) -> Option<u8> {
         match addr & 0x2007 {
             0x2002 => {
@@ -256,16 +587,8 @@ impl Ppu {
         match addr & 0x2007 {
             0x2000 => {
                 // PPUCTRL
-                println!("PPUCTRL write: {:#04X}", value);
-                let nmi_was_enabled = (self.control & 0x80) != 0;
                 self.control = value;
                 self.temp_addr = (self.temp_addr & 0xF3FF) | (((value as u16) & 0x03) << 10);
-
-                let nmi_is_enabled = (self.control & 0x80) != 0;
-                if !nmi_was_enabled && nmi_is_enabled && (self.status & 0x80) != 0 {
-                    self.nmi_pending = true;
-                    println!("NMI triggered immediately on $2000 write");
-                }
             }
             0x2001 => {
                 // PPUMASK
@@ -300,13 +623,14 @@ impl Ppu {
                 } else {
                     self.temp_addr = (self.temp_addr & 0xFF00) | (value as u16);
                     self.vram_addr = self.temp_addr;
+                    // The new address is driven onto the PPU bus
+                    self.observe_address(self.vram_addr & 0x3FFF);
                 }
                 self.addr_latch = !self.addr_latch;
             }
             0x2007 => {
                 // PPUDATA
                 let addr = self.vram_addr & 0x3FFF;
-                println!("PPUDATA write: {:#04X} to {:#04X}", value, addr);
                 self.write_vram(addr, value);
                 self.vram_addr =
                     self.vram_addr
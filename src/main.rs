@@ -5,6 +5,31 @@ mod hardware;
 mod ui_iced;
 mod utils;
 
+fn record_audio(
+    rom_path: &str,
+    wav_path: &str,
+    frames: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Headless run that records the APU output to a WAV file
+    let mut emulator = hardware::Hardware::new();
+    emulator.load_rom(rom_path)?;
+    emulator.set_audio_sink(Box::new(hardware::WavSink::create(wav_path, 44_100)?));
+    for _ in 0..frames {
+        emulator.tick()?;
+    }
+    emulator.flush_audio()?;
+    Ok(())
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 4 && args[1] == "--record-audio" {
+        let frames = args.get(4).and_then(|f| f.parse().ok()).unwrap_or(600);
+        if let Err(err) = record_audio(&args[2], &args[3], frames) {
+            eprintln!("Error recording audio: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
     Nes::run(Settings::default()).expect("Failed to run NES application");
 }
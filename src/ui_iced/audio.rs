@@ -0,0 +1,63 @@
+use std::error::Error;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+
+use crate::hardware::AudioBuffer;
+
+// Default output device of the system, played from a RingBufferSink
+pub struct AudioOutput {
+    device: Device,
+    config: cpal::SupportedStreamConfig,
+}
+
+impl AudioOutput {
+    pub fn open() -> Result<Self, Box<dyn Error>> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("no output device")?;
+        let config = device.default_output_config()?;
+        Ok(AudioOutput { device, config })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.config.sample_rate().0
+    }
+
+    pub fn play(&self, buffer: AudioBuffer) -> Result<Stream, Box<dyn Error>> {
+        // The stream's callback drains the buffer, dropping the stream stops playback
+        let config = self.config.config();
+        let stream = match self.config.sample_format() {
+            SampleFormat::F32 => build_stream::<f32>(&self.device, &config, buffer)?,
+            SampleFormat::I16 => build_stream::<i16>(&self.device, &config, buffer)?,
+            SampleFormat::U16 => build_stream::<u16>(&self.device, &config, buffer)?,
+            format => return Err(format!("unsupported sample format {}", format).into()),
+        };
+        stream.play()?;
+        Ok(stream)
+    }
+}
+
+fn build_stream<T: SizedSample + FromSample<f32>>(
+    device: &Device,
+    config: &StreamConfig,
+    buffer: AudioBuffer,
+) -> Result<Stream, cpal::BuildStreamError> {
+    let channels = config.channels as usize;
+    let mut mono = Vec::new();
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            // The APU is mono, every channel gets the same sample. Silence when the
+            // emulator falls behind, the rate control in RingBufferSink catches up
+            mono.resize(data.len() / channels, 0.0);
+            let available = buffer.read(&mut mono);
+            mono[available..].fill(0.0);
+            for (frame, &sample) in data.chunks_mut(channels).zip(&mono) {
+                frame.fill(T::from_sample(sample));
+            }
+        },
+        |err| eprintln!("Audio output error: {}", err),
+        None,
+    )
+}
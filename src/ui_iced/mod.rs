@@ -1,11 +1,16 @@
+mod audio;
+
 use std::time::{Duration, Instant};
 
-use crate::hardware::enums::Registers;
-use crate::hardware::{Hardware, enums};
+use crate::hardware::enums::{Buttons, JamPolicy, Registers};
+use crate::hardware::{
+    AudioBuffer, ButtonState, Hardware, RingBufferSink, SCREEN_HEIGHT, SCREEN_WIDTH, enums,
+};
 use iced::executor;
-use iced::widget::image::Handle;
-use iced::widget::{Button, Column, Image, column, image, row, text, text_input};
-use iced::{Application, Command, Element, Subscription, Theme, time};
+use iced::keyboard::{self, Key, key::Named};
+use iced::widget::image::{FilterMethod, Handle};
+use iced::widget::{Button, Column, Image, checkbox, column, image, row, text, text_input};
+use iced::{Application, Color, Command, ContentFit, Element, Subscription, Theme, time};
 
 #[derive(Default, Debug, Clone)]
 pub struct CpuState {
@@ -25,6 +30,15 @@ pub struct Nes {
     running: bool,
     chr_1_buffer: image::Handle,
     chr_2_buffer: image::Handle,
+    frame_buffer: image::Handle,
+    scale: u32,
+    aspect_correct: bool,
+    stop_on_jam: bool,
+    buttons: ButtonState,
+    audio: AudioBuffer,
+    _audio_stream: Option<cpal::Stream>, // Plays `audio` until dropped
+    load_error: Option<String>,          // Shown until dismissed
+    disk_side: usize,
     step_size: u32,
     palette: Vec<Vec<[u8; 4]>>,
 }
@@ -74,6 +88,35 @@ impl Nes {
         }
         return palette_list;
     }
+
+    fn update_frame(&mut self) {
+        self.frame_buffer =
+            image::Handle::from_pixels(SCREEN_WIDTH, SCREEN_HEIGHT, self.emulator.frame().to_vec());
+    }
+
+    fn key_to_button(key: Key) -> Option<Buttons> {
+        match key.as_ref() {
+            Key::Named(Named::ArrowUp) => Some(Buttons::Up),
+            Key::Named(Named::ArrowDown) => Some(Buttons::Down),
+            Key::Named(Named::ArrowLeft) => Some(Buttons::Left),
+            Key::Named(Named::ArrowRight) => Some(Buttons::Right),
+            Key::Named(Named::Enter) => Some(Buttons::Start),
+            Key::Named(Named::Shift) => Some(Buttons::Select),
+            Key::Character("x") => Some(Buttons::A),
+            Key::Character("z") => Some(Buttons::B),
+            _ => None,
+        }
+    }
+
+    fn screen_size(&self) -> (u32, u32) {
+        // NES pixels are 8:7 (slightly wider than tall) on a real TV
+        let width = if self.aspect_correct {
+            SCREEN_WIDTH * self.scale * 8 / 7
+        } else {
+            SCREEN_WIDTH * self.scale
+        };
+        (width, SCREEN_HEIGHT * self.scale)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -81,12 +124,19 @@ pub enum NesMessage {
     Tick,
     LoadRom(String),
     Start,
-    Noop,         // No operation message for handling other events
-    Step(u32),    // Step message to control the number of steps
-    SetStep(u32), // Set the step size
+    Noop,                  // No operation message for handling other events
+    Step(u32),             // Step message to control the number of steps
+    SetStep(u32),          // Set the step size
+    SetScale(u32),         // Integer scale of the game viewport
+    SetAspect(bool),       // Toggle 8:7 pixel aspect ratio
+    SetStopOnJam(bool),    // Pause emulation when the CPU hits a KIL opcode
+    Button(Buttons, bool), // Controller button pressed/released
+    DismissError,          // Close the ROM loading error
+    SwitchDiskSide,        // Insert the next side of an FDS disk
 }
 
 const FPS: u64 = 60;
+const SAMPLE_RATE: u32 = 44_100; // Used when there is no output device
 
 impl Application for Nes {
     type Executor = executor::Default;
@@ -96,7 +146,23 @@ impl Application for Nes {
 
     fn new(_flags: ()) -> (Self, Command<NesMessage>) {
         // Initialize the application with a default state.
-        let emulator = Hardware::new();
+        let mut emulator = Hardware::new();
+        let output = audio::AudioOutput::open()
+            .inspect_err(|err| eprintln!("No audio output: {}", err))
+            .ok();
+        let sample_rate = output
+            .as_ref()
+            .map_or(SAMPLE_RATE, |output| output.sample_rate());
+        // Roughly 100ms of audio, the emulator steers its rate to keep it half full
+        let audio_sink = RingBufferSink::new(sample_rate, sample_rate as usize / 10);
+        let audio = audio_sink.buffer();
+        emulator.set_audio_sink(Box::new(audio_sink));
+        let audio_stream = output.and_then(|output| {
+            output
+                .play(audio.clone())
+                .inspect_err(|err| eprintln!("Could not start audio: {}", err))
+                .ok()
+        });
         let palette = emulator.get_palette();
         let nes = Nes {
             cpu_state: CpuState::default(),
@@ -106,6 +172,19 @@ impl Application for Nes {
             emulator,
             chr_1_buffer: image::Handle::from_pixels(128, 128, vec![0; 128 * 128 * 4]),
             chr_2_buffer: image::Handle::from_pixels(128, 128, vec![0; 128 * 128 * 4]),
+            frame_buffer: image::Handle::from_pixels(
+                SCREEN_WIDTH,
+                SCREEN_HEIGHT,
+                vec![0; (SCREEN_WIDTH * SCREEN_HEIGHT * 4) as usize],
+            ),
+            scale: 2,
+            aspect_correct: false,
+            stop_on_jam: false,
+            buttons: ButtonState::default(),
+            audio,
+            _audio_stream: audio_stream,
+            load_error: None,
+            disk_side: 0,
             step_size: 1,
             palette: Nes::create_palette(palette),
         };
@@ -119,14 +198,31 @@ impl Application for Nes {
     // The update method now handles the new `Tick` message
     fn update(&mut self, message: NesMessage) -> Command<NesMessage> {
         match message {
-            NesMessage::LoadRom(path) => {
-                self.emulator.load_rom(path.as_str()).unwrap_or_else(|err| {
-                    eprintln!("Error loading ROM: {}", err);
-                });
-                self.chr_1_buffer =
-                    image::Handle::from_pixels(128, 128, self.emulator.get_chr_image(0).to_vec());
-                self.chr_2_buffer =
-                    image::Handle::from_pixels(128, 128, self.emulator.get_chr_image(1).to_vec());
+            NesMessage::LoadRom(path) => match self.emulator.load_rom(path.as_str()) {
+                Ok(()) => {
+                    self.disk_side = 0;
+                    self.load_error = None;
+                    self.chr_1_buffer = image::Handle::from_pixels(
+                        128,
+                        128,
+                        self.emulator.get_chr_image(0).to_vec(),
+                    );
+                    self.chr_2_buffer = image::Handle::from_pixels(
+                        128,
+                        128,
+                        self.emulator.get_chr_image(1).to_vec(),
+                    );
+                }
+                Err(err) => {
+                    self.load_error = Some(format!("Error loading {}: {}", path, err));
+                }
+            },
+            NesMessage::SwitchDiskSide => {
+                self.disk_side = (self.disk_side + 1) % self.emulator.disk_sides().max(1);
+                self.emulator.insert_disk(Some(self.disk_side));
+            }
+            NesMessage::DismissError => {
+                self.load_error = None;
             }
             NesMessage::Start => {
                 self.running = !self.running;
@@ -148,6 +244,7 @@ impl Application for Nes {
                     self.cpu_state.s = self.emulator.get_cpu_reg(Registers::S);
                     self.cpu_state.pc = self.emulator.get_pc();
                 }
+                self.update_frame();
             }
             NesMessage::Tick => {
                 // This is where you would call your emulator's `tick` function.
@@ -159,9 +256,10 @@ impl Application for Nes {
                     self.fps = (1.0 / (now - self.last_tick.unwrap_or(now)).as_secs_f32()) as u32;
                     self.last_tick = Some(now);
 
-                    self.emulator.tick().unwrap_or_else(|err| {
+                    if let Err(err) = self.emulator.tick() {
                         eprintln!("Error during tick: {}", err);
-                    });
+                        self.running = false;
+                    }
 
                     self.cpu_state.a = self.emulator.get_cpu_reg(Registers::A);
                     self.cpu_state.x = self.emulator.get_cpu_reg(Registers::X);
@@ -171,11 +269,30 @@ impl Application for Nes {
                     self.cpu_state.pc = self.emulator.get_pc();
 
                     self.palette = Nes::create_palette(self.emulator.get_palette());
+                    self.update_frame();
                 }
             }
             NesMessage::SetStep(size) => {
                 self.step_size = size;
             }
+            NesMessage::SetScale(scale) => {
+                self.scale = scale;
+            }
+            NesMessage::SetAspect(aspect_correct) => {
+                self.aspect_correct = aspect_correct;
+            }
+            NesMessage::SetStopOnJam(stop_on_jam) => {
+                self.stop_on_jam = stop_on_jam;
+                self.emulator.set_jam_policy(if stop_on_jam {
+                    JamPolicy::Error
+                } else {
+                    JamPolicy::Halt
+                });
+            }
+            NesMessage::Button(button, pressed) => {
+                self.buttons.set(button, pressed);
+                self.emulator.set_buttons(0, self.buttons);
+            }
         }
         Command::none()
     }
@@ -203,7 +320,40 @@ impl Application for Nes {
             self.emulator.get_cycle()
         ));
 
-        let fps_text = text(format!("FPS: {}, ", self.fps));
+        let fps_text = text(format!(
+            "FPS: {}, Audio: {:.0}%, ",
+            self.fps,
+            self.audio.level() * 100.0
+        ));
+
+        let info = self.emulator.rom_info();
+        let rom_text = text(format!(
+            "{:?} mapper {}.{} ({}), PRG {}K, CHR {}K, PRG-RAM {}K{}{}{}, {:?}, {:?}{}",
+            info.format,
+            info.mapper,
+            info.submapper,
+            info.board,
+            info.prg_rom_size / 1024,
+            info.chr_rom_size / 1024,
+            info.prg_ram_total() / 1024,
+            if info.battery { " (battery)" } else { "" },
+            match (info.trainer, info.trainer_loaded) {
+                (true, true) => ", trainer at $7000",
+                (true, false) => ", trainer ignored",
+                _ => "",
+            },
+            match info.misc_roms {
+                0 => String::new(),
+                count => format!(", {} misc ROMs", count),
+            },
+            info.timing,
+            info.console_type,
+            // NES 2.0 byte 15, 0 means unspecified and 1 standard controllers
+            match info.default_expansion_device {
+                0 => String::new(),
+                device => format!(", expansion device {:#04X}", device),
+            }
+        ));
 
         let load_button = Button::new(text("Load ROM"))
             .on_press(NesMessage::LoadRom(String::from("roms/super-mario.nes")));
@@ -221,6 +371,24 @@ impl Application for Nes {
 
         let step_button = Button::new(text("Step")).on_press(NesMessage::Step(self.step_size));
 
+        let mut scale_buttons = row![text("Scale:")].spacing(10);
+        for scale in 1..=4 {
+            scale_buttons = scale_buttons.push(
+                Button::new(text(format!("{}x", scale))).on_press(NesMessage::SetScale(scale)),
+            );
+        }
+        let aspect_checkbox =
+            checkbox("8:7 pixel aspect", self.aspect_correct).on_toggle(NesMessage::SetAspect);
+        let jam_checkbox =
+            checkbox("Stop on CPU jam", self.stop_on_jam).on_toggle(NesMessage::SetStopOnJam);
+
+        let (screen_width, screen_height) = self.screen_size();
+        let screen_image = Image::<image::Handle>::new(self.frame_buffer.clone())
+            .width(screen_width as f32)
+            .height(screen_height as f32)
+            .content_fit(ContentFit::Fill)
+            .filter_method(FilterMethod::Nearest);
+
         let chr_1_image = Image::<image::Handle>::new(self.chr_1_buffer.clone())
             .width(512)
             .height(512);
@@ -237,15 +405,51 @@ impl Application for Nes {
         }
 
         let mut row1 = row![fps_text, cpu_state_text, cpu_flags_text];
-        let row_controls = row![load_button, start_button, step_button, step_text];
+        let mut row_controls = row![load_button, start_button, step_button, step_text];
+        if self.emulator.disk_sides() > 0 {
+            // Sides are labelled 1A, 1B, 2A... like on the disk cards
+            let label = format!(
+                "Disk {}{}",
+                self.disk_side / 2 + 1,
+                if self.disk_side.is_multiple_of(2) {
+                    'A'
+                } else {
+                    'B'
+                }
+            );
+            row_controls =
+                row_controls.push(Button::new(text(label)).on_press(NesMessage::SwitchDiskSide));
+        }
+        let row_controls = row_controls.push(rom_text);
+        let row_display = row![scale_buttons, aspect_checkbox, jam_checkbox]
+            .padding(10)
+            .spacing(20);
         row1 = row1.padding(10).spacing(10);
         let row2 = row![chr_1_image, chr_2_image];
         //let row3 = row![text("Memory Dump:"), memory_dump_text];
-        column![row1, row_controls, row2, palette_image_list].into()
+        let mut layout = column![row1, row_controls];
+        if let Some(err) = &self.load_error {
+            let error_text = text(err).style(Color::from_rgb(0.9, 0.2, 0.2));
+            let dismiss_button = Button::new(text("OK")).on_press(NesMessage::DismissError);
+            layout = layout.push(row![error_text, dismiss_button].padding(10).spacing(10));
+        }
+        layout
+            .push(row_display)
+            .push(screen_image)
+            .push(row2)
+            .push(palette_image_list)
+            .into()
     }
 
     fn subscription(&self) -> Subscription<NesMessage> {
         // This is a common interval for NES emulation (60Hz refresh rate).
-        time::every(Duration::from_millis(1000 / FPS)).map(|_| NesMessage::Tick)
+        let tick = time::every(Duration::from_millis(1000 / FPS)).map(|_| NesMessage::Tick);
+        let key_press = keyboard::on_key_press(|key, _modifiers| {
+            Nes::key_to_button(key).map(|button| NesMessage::Button(button, true))
+        });
+        let key_release = keyboard::on_key_release(|key, _modifiers| {
+            Nes::key_to_button(key).map(|button| NesMessage::Button(button, false))
+        });
+        Subscription::batch([tick, key_press, key_release])
     }
 }